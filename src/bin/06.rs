@@ -1,6 +0,0 @@
-use std::io;
-
-fn main() -> io::Result<()> {
-    println!("Quadratic equation");
-    Ok(())
-}
@@ -0,0 +1,683 @@
+use std::cmp::Reverse;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use crate::error::{self, Line};
+use crate::{Error, Load, Solution};
+
+/// Spelled-out digits from zero to nine in each built-in language.
+const LANGUAGES: &[(&str, [&str; 10])] = &[
+    (
+        "english",
+        [
+            "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ],
+    ),
+    (
+        "german",
+        [
+            "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ],
+    ),
+    (
+        "french",
+        [
+            "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+        ],
+    ),
+    (
+        "spanish",
+        [
+            "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+        ],
+    ),
+];
+
+/// Words that count as digits besides numerals.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+    ignore_case: bool,
+}
+
+impl Vocabulary {
+    /// Built-in words for one to nine in `language`, and for zero if `zero`.
+    pub fn builtin(language: &str, zero: bool) -> Option<Self> {
+        let (_, words) = LANGUAGES.iter().find(|(name, _)| *name == language)?;
+        let words = (0..)
+            .zip(words)
+            .skip(if zero { 0 } else { 1 })
+            .map(|(value, word)| (word.to_string(), value))
+            .collect();
+        Some(Self {
+            words,
+            ignore_case: false,
+        })
+    }
+
+    pub fn english() -> Self {
+        Self::builtin("english", false).unwrap()
+    }
+
+    /// Word list with a word and the digit it stands for on each line, e.g.
+    /// `uno 1`.
+    pub fn parse(list: &str) -> Result<Self, Error> {
+        let words = error::lines(list)
+            .filter(|line| !line.text.trim().is_empty())
+            .map(parse_word)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            words,
+            ignore_case: false,
+        })
+    }
+
+    pub fn ignore_case(self, ignore_case: bool) -> Self {
+        Self {
+            ignore_case,
+            ..self
+        }
+    }
+
+    /// Digit starting at `rest`, either a numeral or one of the words, with
+    /// the text it was written as. Words may overlap, so each position is
+    /// tried on its own, and the longest word starting there wins whatever
+    /// the order of the list.
+    fn digit_at<'a>(&self, rest: &'a str) -> Option<(&'a str, u32)> {
+        let c = rest.chars().next()?;
+        if let Some(digit) = c.to_digit(10) {
+            return Some((&rest[..c.len_utf8()], digit));
+        }
+        self.words
+            .iter()
+            .filter_map(|(word, value)| {
+                let len = self.prefix_len(rest, word)?;
+                Some((&rest[..len], *value))
+            })
+            .min_by_key(|(text, _)| Reverse(text.len()))
+    }
+
+    /// Length in bytes of `word` at the start of `rest`, if it is there.
+    fn prefix_len(&self, rest: &str, word: &str) -> Option<usize> {
+        if !self.ignore_case {
+            return rest.starts_with(word).then_some(word.len());
+        }
+        let mut chars = rest.char_indices();
+        let mut len = 0;
+        for w in word.chars() {
+            let (i, c) = chars.next()?;
+            if c != w && !c.to_lowercase().eq(w.to_lowercase()) {
+                return None;
+            }
+            len = i + c.len_utf8();
+        }
+        Some(len)
+    }
+
+    /// First digit searching from the left and last from the right.
+    pub fn first_last<'a>(&self, s: &'a str) -> Option<(Digit<'a>, Digit<'a>)> {
+        let digit = |(start, _)| {
+            let (text, value) = self.digit_at(&s[start..])?;
+            Some(Digit {
+                column: s[..start].chars().count() + 1,
+                text,
+                value,
+            })
+        };
+        Some((
+            s.char_indices().find_map(digit)?,
+            s.char_indices().rev().find_map(digit)?,
+        ))
+    }
+
+    /// First and last digit as a two-digit number.
+    pub fn value(&self, s: &str) -> Option<u32> {
+        let (first, last) = self.first_last(s)?;
+        Some(first.value * 10 + last.value)
+    }
+}
+
+/// Digit found in a line, with its 1-based column and how it was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Digit<'a> {
+    pub column: usize,
+    pub text: &'a str,
+    pub value: u32,
+}
+
+impl Display for Digit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' at {}", self.text, self.column)
+    }
+}
+
+/// Word list in the file at `path`.
+fn read_words(path: &str) -> Result<Vocabulary, Error> {
+    let list = fs::read_to_string(path).map_err(|e| Error::new(e).in_file(path))?;
+    parse_word_file(&list, path)
+}
+
+/// Word list given as the contents of the file at `path`, reporting errors
+/// against that file.
+fn parse_word_file(list: &str, path: &str) -> Result<Vocabulary, Error> {
+    Vocabulary::parse(list).map_err(|e| e.in_file(path))
+}
+
+fn parse_word(line: Line) -> Result<(String, u32), Error> {
+    // uno 1
+    let (word, digit) = line
+        .text
+        .trim()
+        .rsplit_once(char::is_whitespace)
+        .ok_or_else(|| line.missing("word and digit"))?;
+    let digit = digit.trim();
+    match line.parse(digit)? {
+        value @ 0..=9 => Ok((word.trim().to_string(), value)),
+        _ => Err(line.error(digit, format!("'{}' is not a digit", digit))),
+    }
+}
+
+fn get_value(s: &str) -> Option<u32> {
+    Vocabulary::default().value(s)
+}
+
+fn get_real_value(s: &str, words: &Vocabulary) -> Option<u32> {
+    words.value(s)
+}
+
+/// What to do with a line without any digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Fail, naming the first such lines.
+    #[default]
+    Strict,
+    /// Leave the line out, naming it in the diagnostics.
+    Lenient,
+}
+
+/// Most lines without a digit named in an error; the rest are only counted.
+const NAMED: usize = 10;
+
+/// Sum of the values of one part, and the lines that had none.
+#[derive(Debug, Default)]
+struct Sum {
+    total: u64,
+    missing: usize,
+    named: Vec<usize>,
+}
+
+impl Sum {
+    fn add(&mut self, number: usize, value: Option<u32>) {
+        match value {
+            Some(value) => self.total += u64::from(value),
+            None => {
+                self.missing += 1;
+                if self.named.len() < NAMED {
+                    self.named.push(number);
+                }
+            }
+        }
+    }
+
+    fn finish(self, part: u8) -> Result<u64, Error> {
+        let Some(&first) = self.named.first() else {
+            return Ok(self.total);
+        };
+        let mut lines: Vec<_> = self.named.iter().map(|n| n.to_string()).collect();
+        if self.missing > self.named.len() {
+            let last = lines.pop().unwrap_or_default();
+            let more = self.missing - self.named.len();
+            lines.push(format!("{} and {} more", last, more));
+        }
+        Err(Error::at(
+            first,
+            1,
+            format!(
+                "part {}: no digit on line{} {}",
+                part,
+                if self.missing > 1 { "s" } else { "" },
+                lines.join(", ")
+            ),
+        ))
+    }
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<String>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part1(lines: &Self::Input) -> Result<u64, Error> {
+        let mut sum = Sum::default();
+        for (number, line) in (1..).zip(lines) {
+            sum.add(number, get_value(line));
+        }
+        sum.finish(1)
+    }
+
+    fn part2(lines: &Self::Input) -> Result<u64, Error> {
+        let words = Vocabulary::english();
+        let mut sum = Sum::default();
+        for (number, line) in (1..).zip(lines) {
+            sum.add(number, get_real_value(line, &words));
+        }
+        sum.finish(2)
+    }
+}
+
+/// Sums of the calibration values of every line, with numerals only and
+/// with spelled digits too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Totals {
+    pub numerals: u64,
+    pub spelled: u64,
+}
+
+/// How much to write about each line while streaming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Detail {
+    #[default]
+    None,
+    /// Both values of the line.
+    Values,
+    /// Which digits were chosen for both values of the line.
+    Explain,
+}
+
+/// Both sums in one pass over `reader`, holding a single line at a time and
+/// writing what `detail` asks for about each line to `out` as it goes. Lines
+/// skipped in lenient mode are named in `log`.
+pub fn stream<W: Write + ?Sized, L: Write + ?Sized>(
+    mut reader: impl BufRead,
+    words: &Vocabulary,
+    mode: Mode,
+    detail: Detail,
+    out: &mut W,
+    log: &mut L,
+) -> Result<Totals, Error> {
+    let numerals = Vocabulary::default();
+    let (mut part1, mut part2) = (Sum::default(), Sum::default());
+    let mut buf = String::new();
+    for number in 1.. {
+        buf.clear();
+        match reader.read_line(&mut buf) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => return Err(Error::at(number, 1, e)),
+        }
+        let line = buf.trim_end_matches(['\n', '\r']);
+        let digits = [numerals.first_last(line), words.first_last(line)];
+        let values = digits.map(|x| x.map(|(first, last)| first.value * 10 + last.value));
+        part1.add(number, values[0]);
+        part2.add(number, values[1]);
+        if mode == Mode::Lenient {
+            for (part, value) in (1..).zip(values) {
+                if value.is_none() {
+                    writeln!(log, "line {}: no digit for part {}, skipped", number, part)
+                        .map_err(Error::new)?;
+                }
+            }
+        }
+        let written = match detail {
+            Detail::None => Ok(()),
+            Detail::Values => {
+                let [a, b] = values.map(|x| x.map_or("-".to_string(), |x| x.to_string()));
+                writeln!(out, "{}: {} {}", number, a, b)
+            }
+            Detail::Explain => {
+                let [a, b] = digits.map(|x| match x {
+                    Some((first, last)) => {
+                        format!("{} and {} = {}", first, last, first.value * 10 + last.value)
+                    }
+                    None => "no digit".to_string(),
+                });
+                writeln!(
+                    out,
+                    "{}: {}\n  part 1: {}\n  part 2: {}",
+                    number, line, a, b
+                )
+            }
+        };
+        written.map_err(Error::new)?;
+    }
+    if mode == Mode::Lenient {
+        return Ok(Totals {
+            numerals: part1.total,
+            spelled: part2.total,
+        });
+    }
+    Ok(Totals {
+        numerals: part1.finish(1)?,
+        spelled: part2.finish(2)?,
+    })
+}
+
+const COMMANDS: &str = "commands: calibrate [OPTIONS] | stream [OPTIONS] < FILE
+options: --lines or --explain, --lenient, --language NAME or --words FILE, --zero, --ignore-case";
+
+/// Options shared by `calibrate` and `stream`.
+struct Options {
+    words: Vocabulary,
+    mode: Mode,
+    detail: Detail,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, Error> {
+    let (mut language, mut file, mut zero, mut ignore_case) = ("english", None, false, false);
+    let (mut mode, mut detail) = (Mode::Strict, Detail::None);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--language" => language = args.next().ok_or_else(|| Error::usage(COMMANDS))?,
+            "--words" => file = Some(args.next().ok_or_else(|| Error::usage(COMMANDS))?),
+            "--zero" => zero = true,
+            "--ignore-case" => ignore_case = true,
+            "--lines" => detail = Detail::Values,
+            "--explain" => detail = Detail::Explain,
+            "--lenient" => mode = Mode::Lenient,
+            _ => return Err(Error::usage(COMMANDS)),
+        }
+    }
+    if zero && file.is_some() {
+        return Err(Error::usage(
+            "--zero only applies to built-in languages, not to --words",
+        ));
+    }
+    let words = match file {
+        Some(path) => read_words(path)?,
+        None => Vocabulary::builtin(language, zero).ok_or_else(|| {
+            let names: Vec<_> = LANGUAGES.iter().map(|(name, _)| *name).collect();
+            Error::usage(format!(
+                "unknown language '{}', expected one of {}",
+                language,
+                names.join(", ")
+            ))
+        })?,
+    }
+    .ignore_case(ignore_case);
+    Ok(Options {
+        words,
+        mode,
+        detail,
+    })
+}
+
+/// Both sums of `reader`, after anything asked for about each line.
+fn run_stream(
+    reader: impl BufRead,
+    out: &mut dyn Write,
+    log: &mut dyn Write,
+    options: Options,
+) -> Result<(), Error> {
+    let totals = stream(
+        reader,
+        &options.words,
+        options.mode,
+        options.detail,
+        out,
+        log,
+    )?;
+    writeln!(
+        out,
+        "part 1: {}\npart 2: {}",
+        totals.numerals, totals.spelled
+    )
+    .map_err(Error::new)
+}
+
+/// Both parts with another vocabulary: a built-in language (english,
+/// german, french or spanish) or a word list from a file.
+pub fn command(input: Load, command: &str, args: &[String]) -> Result<String, Error> {
+    if command != "calibrate" {
+        return Err(Error::usage(COMMANDS));
+    }
+    let options = parse_options(args)?;
+    let mut out = Vec::new();
+    run_stream(input()?.as_bytes(), &mut out, &mut io::stderr(), options)?;
+    String::from_utf8(out).map_err(Error::new)
+}
+
+/// Like `calibrate`, over standard input or anything else too large to read
+/// into memory first.
+pub fn stream_command(
+    reader: &mut dyn BufRead,
+    out: &mut dyn Write,
+    log: &mut dyn Write,
+    args: &[String],
+) -> Result<(), Error> {
+    run_stream(reader, out, log, parse_options(args)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+    const EXAMPLE_REAL: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day01::solve_part1(EXAMPLE).unwrap(), 142);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day01::solve_part2(EXAMPLE_REAL).unwrap(), 281);
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let words = Vocabulary::english();
+        let get_real_value = |s: &str| get_real_value(s, &words);
+        let mut pairs = 0;
+        for (first, a) in &words.words {
+            for (second, b) in &words.words {
+                // every way the end of `first` can be the start of `second`
+                for k in 1..first.len().min(second.len()) {
+                    if first.ends_with(&second[..k]) {
+                        let merged = format!("x{}{}x", first, &second[k..]);
+                        assert_eq!(get_real_value(&merged), Some(a * 10 + b), "{}", merged);
+                        pairs += 1;
+                    }
+                }
+            }
+        }
+        // oneight, twone, threeight, fiveight, sevenine, eightwo, eighthree,
+        // nineight
+        assert_eq!(pairs, 8);
+        assert_eq!(get_real_value("eightwo"), Some(82));
+        assert_eq!(get_real_value("twone"), Some(21));
+        assert_eq!(get_real_value("abc"), None);
+        assert_eq!(get_value("oneight"), None);
+    }
+
+    #[test]
+    fn other_vocabularies() {
+        let german = Vocabulary::builtin("german", false).unwrap();
+        assert_eq!(get_real_value("dreiundneunzig", &german), Some(39));
+        assert_eq!(get_real_value("fünfzehn", &german), Some(55));
+        assert_eq!(get_real_value("nullacht", &german), Some(88));
+        let german = Vocabulary::builtin("german", true).unwrap();
+        assert_eq!(get_real_value("nullacht", &german), Some(8));
+
+        let english = Vocabulary::english();
+        assert_eq!(get_real_value("xONE2", &english), Some(22));
+        assert_eq!(
+            get_real_value("xONE2", &english.ignore_case(true)),
+            Some(12)
+        );
+        let french = Vocabulary::builtin("french", true)
+            .unwrap()
+            .ignore_case(true);
+        assert_eq!(get_real_value("ZÉROtroisx", &french), Some(3));
+
+        let words = Vocabulary::parse("uno 1\n\ndos 2\n").unwrap();
+        assert_eq!(get_real_value("dosuno", &words), Some(21));
+        // the longest word wins, whichever comes first in the list
+        for list in ["sept 7\nseptante 0\n", "septante 0\nsept 7\n"] {
+            let words = Vocabulary::parse(list).unwrap();
+            assert_eq!(words.digit_at("septantes"), Some(("septante", 0)));
+            assert_eq!(words.digit_at("septs"), Some(("sept", 7)));
+        }
+        let args = ["--words", "w.txt", "--zero"].map(String::from);
+        let err = parse_options(&args).err().unwrap();
+        assert_eq!(err.origin(), &error::Origin::Usage);
+
+        let err = Vocabulary::parse("uno 1\ndiez 10\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(6)));
+
+        let err = parse_word_file("uno 1\ndiez 10\n", "words.txt").unwrap_err();
+        assert_eq!(err.to_string(), "words.txt:2:6: '10' is not a digit");
+    }
+
+    #[test]
+    fn stream_matches_parts() {
+        let words = Vocabulary::english();
+        let mut out = Vec::new();
+        let totals = stream(
+            EXAMPLE.as_bytes(),
+            &words,
+            Mode::Strict,
+            Detail::None,
+            &mut out,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(totals.numerals, Day01::solve_part1(EXAMPLE).unwrap());
+        assert_eq!(totals.spelled, 142);
+        assert!(out.is_empty());
+
+        let input = "1abc2\r\nxtwone3four\nnothing";
+        let totals = stream(
+            input.as_bytes(),
+            &words,
+            Mode::Lenient,
+            Detail::Values,
+            &mut out,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "1: 12 12\n2: 33 24\n3: - -\n"
+        );
+        assert_eq!(
+            totals,
+            Totals {
+                numerals: 45,
+                spelled: 36
+            }
+        );
+
+        let err = stream(
+            &b"12\n\xff\n"[..],
+            &words,
+            Mode::Strict,
+            Detail::None,
+            &mut Vec::new(),
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err.line(), Some(2));
+    }
+
+    #[test]
+    fn lines_without_digits() {
+        let err = Day01::solve_part1(EXAMPLE_REAL).unwrap_err();
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(err.message(), "part 1: no digit on line 2");
+        let err = Day01::solve_part2("one\ntwo\nx\n").unwrap_err();
+        assert_eq!(err.message(), "part 2: no digit on line 3");
+
+        let words = Vocabulary::english();
+        let input = "x\ntwo\n";
+        let err = stream(
+            input.as_bytes(),
+            &words,
+            Mode::Strict,
+            Detail::None,
+            &mut Vec::new(),
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err.message(), "part 1: no digit on lines 1, 2");
+        let (mut out, mut log) = (Vec::new(), Vec::new());
+        let totals = stream(
+            input.as_bytes(),
+            &words,
+            Mode::Lenient,
+            Detail::Values,
+            &mut out,
+            &mut log,
+        )
+        .unwrap();
+        assert_eq!(
+            totals,
+            Totals {
+                numerals: 0,
+                spelled: 22
+            }
+        );
+        assert_eq!(String::from_utf8(out).unwrap(), "1: - -\n2: - 22\n");
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "line 1: no digit for part 1, skipped
+line 1: no digit for part 2, skipped
+line 2: no digit for part 1, skipped
+"
+        );
+
+        let input = "x\n".repeat(12);
+        let err = Day01::solve_part1(&input).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "part 1: no digit on lines 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 and 2 more"
+        );
+    }
+
+    #[test]
+    fn explain_chosen_digits() {
+        let mut out = Vec::new();
+        let words = Vocabulary::english();
+        stream(
+            "xtwone3four\n".as_bytes(),
+            &words,
+            Mode::Strict,
+            Detail::Explain,
+            &mut out,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "1: xtwone3four
+  part 1: '3' at 7 and '3' at 7 = 33
+  part 2: 'two' at 2 and 'four' at 8 = 24
+"
+        );
+        let (first, last) = words.ignore_case(true).first_last("ÉxEIGHTwo").unwrap();
+        assert_eq!((first.text, first.column), ("EIGHT", 3));
+        assert_eq!((last.text, last.column), ("Two", 7));
+    }
+}
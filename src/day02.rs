@@ -0,0 +1,530 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Write};
+use std::fs;
+use std::str::FromStr;
+
+use crate::error::{self, Line};
+use crate::{Error, Load, Solution};
+
+/// Multiset of cubes, counted by colour.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Cubes {
+    counts: BTreeMap<String, u32>,
+}
+
+impl Cubes {
+    /// Number of cubes of `colour`, zero if there are none.
+    pub fn get(&self, colour: &str) -> u32 {
+        self.counts.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.counts.iter().map(|(colour, &n)| (colour.as_str(), n))
+    }
+
+    /// Number of cubes of all colours together.
+    pub fn total(&self) -> u64 {
+        self.counts.values().map(|&n| u64::from(n)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.values().all(|&n| n == 0)
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.counts.keys().map(String::as_str)
+    }
+
+    /// Whether there are at most as many cubes of each colour as in `bag`.
+    pub fn fits_in(&self, bag: &Cubes) -> bool {
+        self.iter().all(|(colour, n)| n <= bag.get(colour))
+    }
+
+    /// Cubes of each colour beyond those in `bag`.
+    pub fn excess(&self, bag: &Cubes) -> Cubes {
+        self.iter()
+            .filter(|&(colour, n)| n > bag.get(colour))
+            .map(|(colour, n)| (colour, n - bag.get(colour)))
+            .collect()
+    }
+
+    /// Smallest multiset containing both this one and `other`.
+    pub fn union(&self, other: &Cubes) -> Cubes {
+        let mut union = self.clone();
+        for (colour, n) in other.iter() {
+            let count = union.counts.entry(colour.to_string()).or_default();
+            *count = (*count).max(n);
+        }
+        union
+    }
+
+    /// Product of the number of cubes of each of `colours`.
+    pub fn power<'a>(&self, colours: impl IntoIterator<Item = &'a str>) -> u64 {
+        colours
+            .into_iter()
+            .map(|c| u64::from(self.get(c)))
+            .product()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, u32)> for Cubes {
+    /// Counts of repeated colours are added up.
+    fn from_iter<I: IntoIterator<Item = (S, u32)>>(iter: I) -> Self {
+        let mut counts = BTreeMap::new();
+        for (colour, n) in iter {
+            *counts.entry(colour.into()).or_default() += n;
+        }
+        Cubes { counts }
+    }
+}
+
+impl FromStr for Cubes {
+    type Err = Error;
+
+    /// Each colour may appear only once.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // 3 blue, 4 red
+        let line = Line::new(s);
+        let mut counts = BTreeMap::new();
+        for cube_str in s.split(',') {
+            let cube_str = cube_str.trim();
+            let Some((num, colour)) = cube_str.split_once(' ') else {
+                let message = if cube_str.is_empty() {
+                    "expected count and colour".to_string()
+                } else if cube_str.parse::<u32>().is_ok() {
+                    format!("missing colour after '{}'", cube_str)
+                } else {
+                    format!("missing count before '{}'", cube_str)
+                };
+                return Err(line.error(cube_str, message));
+            };
+            let n = line.parse(num)?;
+            let colour = colour.trim();
+            if colour.is_empty() || !colour.chars().all(char::is_alphabetic) {
+                return Err(line.error(colour, format!("unknown colour '{}'", colour)));
+            }
+            if counts.insert(colour.to_string(), n).is_some() {
+                return Err(line.error(colour, format!("duplicate colour '{}'", colour)));
+            }
+        }
+        Ok(Cubes { counts })
+    }
+}
+
+impl Display for Cubes {
+    /// Same form as parsed, e.g. `4 red, 3 blue`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (colour, n)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} {}", n, colour)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bag of the puzzle, used for part one unless another is given.
+const BAG: &[(&str, u32)] = &[("red", 12), ("green", 13), ("blue", 14)];
+
+/// Sum of the ids of the games that are possible with `bag`.
+pub fn possible(games: &[(u32, Vec<Cubes>)], bag: &Cubes) -> u32 {
+    games
+        .iter()
+        .filter_map(|(id, sets)| sets.iter().all(|x| x.fits_in(bag)).then_some(id))
+        .sum()
+}
+
+/// Smallest bag in which a game with these reveals is possible.
+pub fn minimum(sets: &[Cubes]) -> Cubes {
+    sets.iter().fold(Cubes::default(), |x, y| x.union(y))
+}
+
+/// Every colour in any of `games`.
+fn colours(games: &[(u32, Vec<Cubes>)]) -> BTreeSet<&str> {
+    games
+        .iter()
+        .flat_map(|(_, sets)| sets.iter().flat_map(Cubes::colours))
+        .collect()
+}
+
+/// Bags in which at least `target` games are possible, keeping only those
+/// from which no cube can be taken without making fewer games possible.
+pub fn minimal_bags(games: &[(u32, Vec<Cubes>)], target: usize) -> Vec<Cubes> {
+    let colours: Vec<_> = colours(games).into_iter().collect();
+    let bag = |counts: &[u32]| -> Cubes {
+        colours
+            .iter()
+            .copied()
+            .zip(counts.iter().copied())
+            .collect()
+    };
+    if target == 0 || colours.is_empty() {
+        return if target <= games.len() {
+            vec![bag(&vec![0; colours.len()])]
+        } else {
+            Vec::new()
+        };
+    }
+    let minima: Vec<Vec<u32>> = games
+        .iter()
+        .map(|(_, sets)| {
+            let min = minimum(sets);
+            colours.iter().map(|c| min.get(c)).collect()
+        })
+        .collect();
+
+    // A minimal bag is the smallest bag of the games possible in it, so each
+    // count is one of the games' counts. Try every combination for all but
+    // the last colour; the last one then needs the target-th smallest count
+    // among the games that fit.
+    let last = colours.len() - 1;
+    let values: Vec<Vec<u32>> = (0..last)
+        .map(|i| {
+            let counts: BTreeSet<_> = minima.iter().map(|m| m[i]).collect();
+            counts.into_iter().collect()
+        })
+        .collect();
+    let mut found = BTreeSet::new();
+    let mut index = vec![0; last];
+    loop {
+        let mut fitting: Vec<_> = minima
+            .iter()
+            .filter(|m| (0..last).all(|i| m[i] <= values[i][index[i]]))
+            .collect();
+        if fitting.len() >= target {
+            fitting.sort_by_key(|m| m[last]);
+            let limit = fitting[target - 1][last];
+            let counts = fitting
+                .iter()
+                .filter(|m| m[last] <= limit)
+                .fold(vec![0; colours.len()], |acc, m| {
+                    acc.iter().zip(m.iter()).map(|(&a, &b)| a.max(b)).collect()
+                });
+            found.insert(counts);
+        }
+        // next combination
+        let Some(i) = (0..last).find(|&i| index[i] + 1 < values[i].len()) else {
+            break;
+        };
+        index[i] += 1;
+        index[..i].fill(0);
+    }
+
+    let dominates = |a: &Vec<u32>, b: &Vec<u32>| a != b && a.iter().zip(b).all(|(x, y)| x <= y);
+    found
+        .iter()
+        .filter(|b| !found.iter().any(|a| dominates(a, b)))
+        .map(|b| bag(b))
+        .collect()
+}
+
+/// Index of the first reveal of a game that does not fit in `bag`, and the
+/// cubes it has beyond those in the bag.
+pub fn first_violation(sets: &[Cubes], bag: &Cubes) -> Option<(usize, Cubes)> {
+    sets.iter()
+        .map(|x| x.excess(bag))
+        .enumerate()
+        .find(|(_, excess)| !excess.is_empty())
+}
+
+/// Bag in the file at `path`.
+fn read_bag(path: &str) -> Result<Cubes, Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::new(e).in_file(path))?;
+    parse_bag_file(&text, path)
+}
+
+/// Bag given as the `text` of the file at `path`, with one or more lines
+/// like `12 red, 13 green`, reporting errors against that file.
+fn parse_bag_file(text: &str, path: &str) -> Result<Cubes, Error> {
+    let mut counts = BTreeMap::new();
+    for line in error::lines(text).filter(|l| !l.text.trim().is_empty()) {
+        let cubes: Cubes = line
+            .text
+            .parse()
+            .map_err(|e| line.locate(line.text, e).in_file(path))?;
+        for (colour, n) in cubes.iter() {
+            if counts.insert(colour.to_string(), n).is_some() {
+                let err = line.error(line.text, format!("duplicate colour '{}'", colour));
+                return Err(err.in_file(path));
+            }
+        }
+    }
+    Ok(Cubes { counts })
+}
+
+/// Bag given as `--file PATH` or directly as arguments. No arguments means
+/// the puzzle's bag.
+fn parse_bag(args: &[String]) -> Result<Cubes, Error> {
+    match args {
+        [] => Ok(Cubes::from_iter(BAG.iter().copied())),
+        [flag, path] if flag == "--file" => read_bag(path),
+        _ => args
+            .join(" ")
+            .parse()
+            .map_err(|e: Error| Error::usage(format!("bag: {}", e.message()))),
+    }
+}
+
+fn parse_line(line: Line) -> Result<(u32, Vec<Cubes>), Error> {
+    // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+    let (game, sets) = line
+        .text
+        .split_once(':')
+        .ok_or_else(|| line.missing("':' after game"))?;
+    // Game 1
+    let id = game
+        .strip_prefix("Game ")
+        .ok_or_else(|| line.error(game, format!("expected 'Game <id>', found '{}'", game)))?;
+    let id: u32 = line.parse(id.trim())?;
+    // 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+    let sets = sets
+        .split(';')
+        .enumerate()
+        .map(|(i, x)| {
+            x.parse::<Cubes>().map_err(|e| {
+                line.locate(x, e)
+                    .context(format!("game {}, reveal {}", id, i + 1))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((id, sets))
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<(u32, Vec<Cubes>)>;
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        error::lines(input).map(parse_line).collect()
+    }
+
+    fn part1(games: &Self::Input) -> Result<u32, Error> {
+        Ok(possible(games, &Cubes::from_iter(BAG.iter().copied())))
+    }
+
+    fn part2(games: &Self::Input) -> Result<u64, Error> {
+        // a colour missing from a game has no cubes in its smallest bag
+        let colours = colours(games);
+        Ok(games
+            .iter()
+            .map(|(_, sets)| minimum(sets).power(colours.iter().copied()))
+            .sum())
+    }
+}
+
+const COMMANDS: &str = "commands: possible [BAG] | violations [BAG] | bags GAMES
+where BAG is COUNT COLOUR, ... or --file PATH";
+
+/// Part one against a bag given on the command line or in a file, which
+/// reveal of each game breaks it, and the smallest bags for a number of
+/// possible games.
+pub fn command(input: Load, command: &str, args: &[String]) -> Result<String, Error> {
+    let mut out = String::new();
+    match command {
+        "possible" => {
+            let bag = parse_bag(args)?;
+            let games = Day02::parse(&input()?)?;
+            writeln!(out, "{}", possible(&games, &bag)).unwrap();
+        }
+        "violations" => {
+            let bag = parse_bag(args)?;
+            let games = Day02::parse(&input()?)?;
+            for (id, sets) in &games {
+                match first_violation(sets, &bag) {
+                    None => writeln!(out, "Game {}: possible", id),
+                    Some((i, excess)) => writeln!(
+                        out,
+                        "Game {}: reveal {} ({}) is over by {}",
+                        id,
+                        i + 1,
+                        sets[i],
+                        excess
+                    ),
+                }
+                .unwrap();
+            }
+        }
+        "bags" => {
+            let [target] = args else {
+                return Err(Error::usage(COMMANDS));
+            };
+            let target: usize = target
+                .parse()
+                .map_err(|_| Error::usage(format!("invalid number '{}'", target)))?;
+            let games = Day02::parse(&input()?)?;
+            let bags = minimal_bags(&games, target);
+            for bag in &bags {
+                writeln!(out, "{:>6} {}", bag.total(), bag).unwrap();
+            }
+            let best = bags
+                .iter()
+                .min_by_key(|bag| bag.total())
+                .ok_or_else(|| Error::new(format!("only {} games to choose from", games.len())))?;
+            writeln!(out, "fewest cubes: {} ({})", best, best.total()).unwrap();
+        }
+        _ => return Err(Error::usage(COMMANDS)),
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day02::solve_part1(EXAMPLE).unwrap(), 8);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day02::solve_part2(EXAMPLE).unwrap(), 2286);
+    }
+
+    #[test]
+    fn any_colours() {
+        let input = "\
+Game 1: 3 teal, 2 red; 1 teal, 4 mauve
+Game 2: 2 red, 1 mauve, 1 teal; 5 red
+Game 3: 2 red
+";
+        let games = Day02::parse(input).unwrap();
+        let bag: Cubes = "3 teal, 5 red, 4 mauve".parse().unwrap();
+        assert_eq!(possible(&games, &bag), 6);
+        let bag: Cubes = "2 teal, 5 red, 4 mauve".parse().unwrap();
+        assert_eq!(possible(&games, &bag), 5);
+        // 3 * 2 * 4 + 1 * 5 * 1, and game 3 has no teal or mauve
+        assert_eq!(Day02::part2(&games).unwrap(), 29);
+        assert_eq!(bag.to_string(), "4 mauve, 5 red, 2 teal");
+    }
+
+    #[test]
+    fn errors_point_at_the_token() {
+        let cases = [
+            ("Game 1 3 blue", 1, 14, "expected ':' after game"),
+            ("Gme 1: 3 blue", 1, 1, "expected 'Game <id>', found 'Gme 1'"),
+            ("Game x: 3 blue", 1, 6, "invalid number 'x'"),
+            (
+                "Game 1: 3 blue\nGame 2: 1 red; blue, 2 green",
+                2,
+                16,
+                "game 2, reveal 2: missing count before 'blue'",
+            ),
+            (
+                "Game 3: 1 red, 4",
+                1,
+                16,
+                "game 3, reveal 1: missing colour after '4'",
+            ),
+            (
+                "Game 4: 1 red; 2 green; 3x blue",
+                1,
+                25,
+                "game 4, reveal 3: invalid number '3x'",
+            ),
+            (
+                "Game 5: 1 r3d",
+                1,
+                11,
+                "game 5, reveal 1: unknown colour 'r3d'",
+            ),
+            (
+                "Game 6: 1 red, 2 blue, 3 red",
+                1,
+                26,
+                "game 6, reveal 1: duplicate colour 'red'",
+            ),
+            (
+                "Game 7: 1 red;",
+                1,
+                15,
+                "game 7, reveal 2: expected count and colour",
+            ),
+        ];
+        for (input, line, column, message) in cases {
+            let err = Day02::parse(input).unwrap_err();
+            assert_eq!(
+                (err.line(), err.column(), err.message()),
+                (Some(line), Some(column), message),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn bag_errors_name_their_source() {
+        let err = parse_bag_file("12 red\n13 gr3en, 14 blue\n", "bag.txt").unwrap_err();
+        assert_eq!(err.to_string(), "bag.txt:2:4: unknown colour 'gr3en'");
+        let err = parse_bag_file("12 red\n13 green, 12 red\n", "bag.txt").unwrap_err();
+        assert_eq!(err.to_string(), "bag.txt:2:1: duplicate colour 'red'");
+        let bag = parse_bag_file("12 red\n\n13 green, 14 blue\n", "bag.txt").unwrap();
+        assert_eq!(bag.to_string(), "14 blue, 13 green, 12 red");
+
+        let args: Vec<_> = ["12", "red,", "1x", "green"].map(String::from).into();
+        let err = parse_bag(&args).unwrap_err();
+        assert_eq!(err.to_string(), "bag: invalid number '1x'");
+        assert_eq!(err.origin(), &error::Origin::Usage);
+    }
+
+    #[test]
+    fn minimal_bags_example() {
+        let games = Day02::parse(EXAMPLE).unwrap();
+        let bags = |target| -> Vec<_> {
+            minimal_bags(&games, target)
+                .iter()
+                .map(|b| b.to_string())
+                .collect()
+        };
+        assert_eq!(
+            bags(1),
+            vec![
+                "2 blue, 3 green, 6 red",
+                "4 blue, 3 green, 1 red",
+                "6 blue, 2 green, 4 red"
+            ]
+        );
+        assert_eq!(bags(5), vec!["15 blue, 13 green, 20 red"]);
+        assert!(bags(6).is_empty());
+        // every game possible in a bag has a smallest bag inside it
+        for bag in minimal_bags(&games, 3) {
+            let fit = games
+                .iter()
+                .filter(|(_, sets)| minimum(sets).fits_in(&bag))
+                .count();
+            assert!(fit >= 3);
+        }
+    }
+
+    #[test]
+    fn violations_example() {
+        let games = Day02::parse(EXAMPLE).unwrap();
+        let bag = Cubes::from_iter(BAG.iter().copied());
+        let violations: Vec<_> = games
+            .iter()
+            .map(|(_, sets)| first_violation(sets, &bag).map(|(i, x)| (i, x.to_string())))
+            .collect();
+        assert_eq!(
+            violations,
+            vec![
+                None,
+                None,
+                Some((0, "8 red".to_string())),
+                Some((2, "1 blue, 2 red".to_string())),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,572 @@
+use std::fmt::Write;
+use std::iter;
+use std::str::FromStr;
+
+use crate::grid::{Grid, Pos};
+use crate::{Error, Load, Solution};
+
+/// Which characters count as symbols. Digits never do, being part of
+/// numbers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Symbols {
+    /// Anything but `.`, as in the puzzle.
+    #[default]
+    NotDot,
+    /// Only these characters.
+    Only(String),
+    /// Anything but these characters.
+    Except(String),
+}
+
+impl Symbols {
+    fn contains(&self, c: char) -> bool {
+        !c.is_ascii_digit()
+            && match self {
+                Symbols::NotDot => c != '.',
+                Symbols::Only(chars) => chars.contains(c),
+                Symbols::Except(chars) => !chars.contains(c),
+            }
+    }
+}
+
+/// How many numbers must be next to a gear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Count {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Count {
+    fn matches(self, n: usize) -> bool {
+        match self {
+            Count::Exactly(x) => n == x,
+            Count::AtLeast(x) => n >= x,
+        }
+    }
+}
+
+/// How the numbers next to a gear are combined into its ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Aggregate {
+    #[default]
+    Product,
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    /// The combined values, or `None` if a product or sum overflows.
+    fn apply(self, mut values: impl Iterator<Item = u64>) -> Option<u64> {
+        match self {
+            Aggregate::Product => values.try_fold(1u64, u64::checked_mul),
+            Aggregate::Sum => values.try_fold(0u64, u64::checked_add),
+            Aggregate::Min => Some(values.min().unwrap_or(0)),
+            Aggregate::Max => Some(values.max().unwrap_or(0)),
+        }
+    }
+}
+
+impl FromStr for Aggregate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Aggregate::Product),
+            "sum" => Ok(Aggregate::Sum),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            _ => Err(Error::new(format!(
+                "unknown aggregate '{}', expected product, sum, min or max",
+                s
+            ))),
+        }
+    }
+}
+
+/// What makes a part number and a gear. The default is the puzzle's: any
+/// symbol but `.`, and a `*` next to exactly two numbers, multiplied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub symbols: Symbols,
+    pub gear: char,
+    pub count: Count,
+    pub aggregate: Aggregate,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            symbols: Symbols::NotDot,
+            gear: '*',
+            count: Count::Exactly(2),
+            aggregate: Aggregate::Product,
+        }
+    }
+}
+
+fn line_gear_locations(row: &[char], gear: char) -> impl Iterator<Item = usize> + '_ {
+    // 617*......
+    row.iter()
+        .enumerate()
+        .filter(move |(_, &c)| c == gear)
+        .map(|(i, _)| i)
+}
+
+/// Numbers of a row with the columns they span, `None` for a number too
+/// large for `u32`.
+fn line_numbers(row: &[char]) -> impl Iterator<Item = (usize, usize, Option<u32>)> + '_ {
+    // 467..114..
+    let mut line = row.iter().enumerate();
+    iter::from_fn(move || {
+        let (idxs, digits): (Vec<_>, Vec<_>) = line
+            .by_ref()
+            .skip_while(|(_, c)| !c.is_ascii_digit())
+            .take_while(|(_, c)| c.is_ascii_digit())
+            .filter_map(|(i, c)| Some((i, c.to_digit(10)?)))
+            .unzip();
+        if idxs.is_empty() {
+            None
+        } else {
+            Some((
+                *idxs.iter().min().unwrap(),
+                *idxs.iter().max().unwrap(),
+                digits
+                    .iter()
+                    .try_fold(0u32, |acc, &d| acc.checked_mul(10)?.checked_add(d)),
+            ))
+        }
+    })
+}
+
+/// Cells adjacent to the number spanning columns `s..=e` of `row`.
+fn adjacent<'a>(
+    grid: &'a Grid<char>,
+    row: usize,
+    s: usize,
+    e: usize,
+) -> impl Iterator<Item = Pos> + 'a {
+    (s..=e).flat_map(move |col| grid.neighbours8((row, col)))
+}
+
+/// Number in the schematic, spanning `start..=end` of its row, with the
+/// symbols around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+    pub symbols: Vec<Pos>,
+}
+
+impl Number {
+    pub fn is_part(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+}
+
+/// Possible gear, with the indices of the numbers around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Star {
+    pub pos: Pos,
+    pub numbers: Vec<usize>,
+}
+
+/// Every number and every possible gear of a schematic, with what is next
+/// to each.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schematic {
+    pub rules: Rules,
+    pub numbers: Vec<Number>,
+    pub stars: Vec<Star>,
+}
+
+impl Schematic {
+    pub fn new(grid: &Grid<char>, rules: &Rules) -> Result<Self, Error> {
+        // index of the number covering each cell
+        let mut owners: Grid<Option<usize>> = Grid::new(grid.width(), grid.height(), None);
+        let mut numbers = Vec::new();
+        for (i, row) in grid.rows().enumerate() {
+            for (s, e, value) in line_numbers(row) {
+                let value = value.ok_or_else(|| {
+                    let digits: String = row[s..=e].iter().collect();
+                    Error::at(
+                        i + 1,
+                        s + 1,
+                        format!("number {} does not fit in u32", digits),
+                    )
+                })?;
+                for col in s..=e {
+                    owners[(i, col)] = Some(numbers.len());
+                }
+                let mut symbols: Vec<_> = adjacent(grid, i, s, e)
+                    .filter(|&pos| rules.symbols.contains(grid[pos]))
+                    .collect();
+                symbols.sort_unstable();
+                symbols.dedup();
+                numbers.push(Number {
+                    row: i,
+                    start: s,
+                    end: e,
+                    value,
+                    symbols,
+                });
+            }
+        }
+
+        let mut stars = Vec::new();
+        for (i, row) in grid.rows().enumerate() {
+            for loc in line_gear_locations(row, rules.gear) {
+                let mut adj: Vec<_> = grid
+                    .neighbours8((i, loc))
+                    .filter_map(|pos| owners[pos])
+                    .collect();
+                adj.sort_unstable();
+                adj.dedup();
+                stars.push(Star {
+                    pos: (i, loc),
+                    numbers: adj,
+                });
+            }
+        }
+        Ok(Self {
+            rules: rules.clone(),
+            numbers,
+            stars,
+        })
+    }
+
+    pub fn is_gear(&self, star: &Star) -> bool {
+        self.rules.count.matches(star.numbers.len())
+    }
+
+    /// The numbers around a star combined by the aggregate of the rules.
+    pub fn ratio(&self, star: &Star) -> Result<u64, Error> {
+        let values = star
+            .numbers
+            .iter()
+            .map(|&x| u64::from(self.numbers[x].value));
+        self.rules.aggregate.apply(values).ok_or_else(|| {
+            let (row, col) = star.pos;
+            Error::at(
+                row + 1,
+                col + 1,
+                format!("ratio of gear at {} overflows", show(star.pos)),
+            )
+        })
+    }
+
+    /// Sum of the part numbers.
+    pub fn part_sum(&self) -> u64 {
+        self.numbers
+            .iter()
+            .filter(|n| n.is_part())
+            .map(|n| u64::from(n.value))
+            .sum()
+    }
+
+    /// Sum of the ratios of the gears.
+    pub fn gear_sum(&self) -> Result<u64, Error> {
+        let mut sum = 0u64;
+        for star in self.stars.iter().filter(|star| self.is_gear(star)) {
+            sum = sum
+                .checked_add(self.ratio(star)?)
+                .ok_or_else(|| Error::new("sum of gear ratios overflows"))?;
+        }
+        Ok(sum)
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Grid<char>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Grid::parse(input)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<u64, Error> {
+        Ok(Schematic::new(grid, &Rules::default())?.part_sum())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<u64, Error> {
+        Schematic::new(grid, &Rules::default())?.gear_sum()
+    }
+}
+
+/// Position as 1-based `row:column`, like the locations of errors.
+fn show((row, col): Pos) -> String {
+    format!("{}:{}", row + 1, col + 1)
+}
+
+/// Every number and possible gear with what made it count or not.
+pub fn explain(grid: &Grid<char>, rules: &Rules) -> Result<String, Error> {
+    let schematic = Schematic::new(grid, rules)?;
+    let mut out = String::from("numbers:\n");
+    for n in &schematic.numbers {
+        write!(
+            out,
+            "  {} at row {}, columns {}-{}: ",
+            n.value,
+            n.row + 1,
+            n.start + 1,
+            n.end + 1
+        )
+        .unwrap();
+        if n.is_part() {
+            let symbols: Vec<_> = n
+                .symbols
+                .iter()
+                .map(|&pos| format!("'{}' at {}", grid[pos], show(pos)))
+                .collect();
+            writeln!(out, "part number ({})", symbols.join(", ")).unwrap();
+        } else {
+            writeln!(out, "not a part number").unwrap();
+        }
+    }
+    writeln!(out, "gears:").unwrap();
+    for star in &schematic.stars {
+        let numbers: Vec<_> = star
+            .numbers
+            .iter()
+            .map(|&x| schematic.numbers[x].value.to_string())
+            .collect();
+        write!(
+            out,
+            "  '{}' at {} next to [{}]: ",
+            rules.gear,
+            show(star.pos),
+            numbers.join(", ")
+        )
+        .unwrap();
+        if schematic.is_gear(star) {
+            match schematic.ratio(star) {
+                Ok(ratio) => writeln!(out, "gear, ratio {}", ratio).unwrap(),
+                Err(_) => writeln!(out, "gear, ratio overflows").unwrap(),
+            }
+        } else {
+            writeln!(out, "not a gear").unwrap();
+        }
+    }
+    Ok(out)
+}
+
+const COMMANDS: &str = "commands: solve [--pad] [RULES] | explain [--pad] [RULES]
+rules: --symbols CHARS or --not-symbols CHARS, --gear CHAR, \
+--exactly N or --at-least N, --aggregate product|sum|min|max";
+
+/// Rules from the arguments, and whether to pad short rows.
+fn parse_rules(args: &[String]) -> Result<(Rules, bool), Error> {
+    let mut rules = Rules::default();
+    let mut pad = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--pad" {
+            pad = true;
+            continue;
+        }
+        let value = args.next().ok_or_else(|| Error::usage(COMMANDS))?;
+        let number = || {
+            value
+                .parse()
+                .map_err(|_| Error::usage(format!("invalid number '{}'", value)))
+        };
+        match arg.as_str() {
+            "--symbols" => rules.symbols = Symbols::Only(value.clone()),
+            "--not-symbols" => rules.symbols = Symbols::Except(value.clone()),
+            "--gear" => {
+                let mut chars = value.chars();
+                rules.gear = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        return Err(Error::usage(format!(
+                            "gear must be one character, found '{}'",
+                            value
+                        )))
+                    }
+                };
+            }
+            "--exactly" => rules.count = Count::Exactly(number()?),
+            "--at-least" => rules.count = Count::AtLeast(number()?),
+            "--aggregate" => {
+                rules.aggregate = value
+                    .parse()
+                    .map_err(|e: Error| Error::usage(e.message()))?
+            }
+            _ => return Err(Error::usage(COMMANDS)),
+        }
+    }
+    Ok((rules, pad))
+}
+
+/// Both parts, or an explanation of them, under other rules. Rows shorter
+/// than the longest are rejected unless `--pad` fills them with `.`.
+pub fn command(input: Load, command: &str, args: &[String]) -> Result<String, Error> {
+    if !matches!(command, "solve" | "explain") {
+        return Err(Error::usage(COMMANDS));
+    }
+    let (rules, pad) = parse_rules(args)?;
+    let input = input()?;
+    let grid = if pad {
+        Grid::parse_padded(&input, '.')
+    } else {
+        Day03::parse(&input)?
+    };
+    match command {
+        "solve" => {
+            let schematic = Schematic::new(&grid, &rules)?;
+            Ok(format!(
+                "part 1: {}\npart 2: {}\n",
+                schematic.part_sum(),
+                schematic.gear_sum()?
+            ))
+        }
+        _ => explain(&grid, &rules),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day03::solve_part1(EXAMPLE).unwrap(), 4361);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day03::solve_part2(EXAMPLE).unwrap(), 467835);
+    }
+
+    #[test]
+    fn explain_example() {
+        let out = explain(&Day03::parse(EXAMPLE).unwrap(), &Rules::default()).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines[0], "numbers:");
+        assert_eq!(
+            lines[1],
+            "  467 at row 1, columns 1-3: part number ('*' at 2:4)"
+        );
+        assert_eq!(lines[2], "  114 at row 1, columns 6-8: not a part number");
+        assert_eq!(lines[11], "gears:");
+        assert_eq!(
+            lines[12],
+            "  '*' at 2:4 next to [467, 35]: gear, ratio 16345"
+        );
+        assert_eq!(lines[13], "  '*' at 5:4 next to [617]: not a gear");
+        assert_eq!(lines.len(), 15);
+    }
+
+    #[test]
+    fn ragged_and_multibyte_rows() {
+        let input = "12é..\n..€.7\n3.\n";
+        let err = Day03::parse(input).unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(3), Some(1)));
+        assert_eq!(err.message(), "row 3 has 2 columns, expected 5 like row 1");
+
+        // columns count characters, not bytes
+        let grid = Grid::parse_padded(input, '.');
+        let out = explain(&grid, &Rules::default()).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines[1..4],
+            [
+                "  12 at row 1, columns 1-2: part number ('é' at 1:3, '€' at 2:3)",
+                "  7 at row 2, columns 5-5: not a part number",
+                "  3 at row 3, columns 1-1: not a part number",
+            ]
+        );
+        let out = explain(
+            &grid,
+            &Rules {
+                symbols: Symbols::Except(".é".to_string()),
+                ..Rules::default()
+            },
+        )
+        .unwrap();
+        assert!(out.contains("12 at row 1, columns 1-2: part number ('€' at 2:3)"));
+        assert_eq!(
+            command(&|| Ok(input.to_string()), "solve", &["--pad".to_string()]).unwrap(),
+            "part 1: 12\npart 2: 0\n"
+        );
+    }
+
+    #[test]
+    fn numbers_too_large() {
+        let err = Day03::solve_part1("..99999999999*1\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(3)));
+        assert_eq!(err.message(), "number 99999999999 does not fit in u32");
+    }
+
+    #[test]
+    fn ratios_too_large() {
+        let grid = Day03::parse("4000000000*4000000000\n4000000000...........\n").unwrap();
+        let rules = Rules {
+            count: Count::AtLeast(3),
+            ..Rules::default()
+        };
+        let err = Schematic::new(&grid, &rules)
+            .unwrap()
+            .gear_sum()
+            .unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(11)));
+        assert_eq!(err.message(), "ratio of gear at 1:11 overflows");
+        let out = explain(&grid, &rules).unwrap();
+        assert!(out.ends_with(
+            "'*' at 1:11 next to [4000000000, 4000000000, 4000000000]: gear, ratio overflows\n"
+        ));
+    }
+
+    #[test]
+    fn other_rules() {
+        let grid = Day03::parse(EXAMPLE).unwrap();
+        let sum = |rules: Rules| {
+            let schematic = Schematic::new(&grid, &rules).unwrap();
+            (schematic.part_sum(), schematic.gear_sum().unwrap())
+        };
+        // only '#' and '$' are symbols: 633 and 664
+        let rules = Rules {
+            symbols: Symbols::Only("#$".to_string()),
+            ..Rules::default()
+        };
+        assert_eq!(sum(rules).0, 633 + 664);
+        // everything but '*' is a symbol
+        let rules = Rules {
+            symbols: Symbols::Except(".*".to_string()),
+            ..Rules::default()
+        };
+        assert_eq!(sum(rules).0, 633 + 664 + 592);
+        // every star next to at least one number, summed
+        let rules = Rules {
+            count: Count::AtLeast(1),
+            aggregate: Aggregate::Sum,
+            ..Rules::default()
+        };
+        assert_eq!(sum(rules).1, 467 + 35 + 617 + 755 + 598);
+        let rules = Rules {
+            gear: '+',
+            count: Count::Exactly(1),
+            aggregate: Aggregate::Max,
+            ..Rules::default()
+        };
+        assert_eq!(sum(rules).1, 592);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::error::{self, Line};
+use crate::{Error, Solution};
+
+fn parse_line(line: Line) -> Result<(u32, Vec<u32>, Vec<u32>), Error> {
+    // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    let (prefix, suffix) = line
+        .text
+        .split_once(':')
+        .ok_or_else(|| line.missing("':'"))?;
+    // Card 1
+    let id: u32 = line.parse(
+        prefix
+            .split_ascii_whitespace()
+            .next_back()
+            .unwrap_or(prefix),
+    )?;
+    // 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    let (win, have) = suffix.split_once('|').ok_or_else(|| line.missing("'|'"))?;
+    Ok((
+        id,
+        win.split_ascii_whitespace()
+            .map(|x| line.parse(x))
+            .collect::<Result<_, _>>()?,
+        have.split_ascii_whitespace()
+            .map(|x| line.parse(x))
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+fn card_matches(win: &[u32], have: &[u32]) -> usize {
+    let wins = win.iter().collect::<HashSet<_>>();
+    have.iter().filter(|x| wins.contains(x)).count()
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<(u32, Vec<u32>, Vec<u32>)>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        error::lines(input).map(parse_line).collect()
+    }
+
+    fn part1(cards: &Self::Input) -> Result<u32, Error> {
+        let mut points = 0u32;
+        for (id, win, have) in cards {
+            let count = card_matches(win, have);
+            let Some(shift) = count.checked_sub(1) else {
+                continue;
+            };
+            let card_points = u32::try_from(shift)
+                .ok()
+                .and_then(|x| 1u32.checked_shl(x))
+                .ok_or_else(|| {
+                    Error::new(format!(
+                        "card {} has {} matches, too many points",
+                        id, count
+                    ))
+                })?;
+            points = points
+                .checked_add(card_points)
+                .ok_or_else(|| Error::new("sum of points overflows"))?;
+        }
+        Ok(points)
+    }
+
+    fn part2(cards: &Self::Input) -> Result<u32, Error> {
+        let mut total_cards = 0u32;
+        let mut extras: VecDeque<u32> = VecDeque::new();
+        let overflow = |id: &u32| Error::new(format!("number of cards overflows at card {}", id));
+        for (id, win, have) in cards {
+            let count = card_matches(win, have);
+            let instances = extras
+                .pop_front()
+                .unwrap_or(0)
+                .checked_add(1)
+                .ok_or_else(|| overflow(id))?;
+            total_cards = total_cards
+                .checked_add(instances)
+                .ok_or_else(|| overflow(id))?;
+            for i in 0..count {
+                if let Some(x) = extras.get_mut(i) {
+                    *x = x.checked_add(instances).ok_or_else(|| overflow(id))?;
+                } else {
+                    extras.push_back(instances);
+                }
+            }
+        }
+        Ok(total_cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day04::solve_part1(EXAMPLE).unwrap(), 13);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day04::solve_part2(EXAMPLE).unwrap(), 30);
+    }
+
+    #[test]
+    fn too_many_matches() {
+        let numbers: Vec<_> = (1..=33).map(|x| x.to_string()).collect();
+        let card = format!("Card 7: {} | {}", numbers.join(" "), numbers.join(" "));
+        let err = Day04::solve_part1(&card).unwrap_err();
+        assert_eq!(err.message(), "card 7 has 33 matches, too many points");
+
+        let numbers: Vec<_> = (1..=31).map(|x| x.to_string()).collect();
+        let cards: String = (1..=5)
+            .map(|id| {
+                format!(
+                    "Card {}: {} | {}\n",
+                    id,
+                    numbers.join(" "),
+                    numbers.join(" ")
+                )
+            })
+            .collect();
+        let err = Day04::solve_part1(&cards).unwrap_err();
+        assert_eq!(err.message(), "sum of points overflows");
+    }
+
+    #[test]
+    fn too_many_cards() {
+        let cards: String = (1..=59)
+            .map(|id| format!("Card {}: 1 2 | 1 2\n", id))
+            .collect();
+        let err = Day04::solve_part2(&cards).unwrap_err();
+        assert_eq!(err.message(), "number of cards overflows at card 44");
+    }
+}
@@ -0,0 +1,822 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
+
+use crate::error::{self, Line};
+use crate::interval::{Interval, IntervalMap, IntervalSet};
+use crate::{Error, Load, Solution};
+
+/// Overflow-checked arithmetic, so that ranges reaching the top of `T` are
+/// reported rather than wrapping.
+pub trait Checked: Sized + Copy + Ord + Display {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked {
+    ($($t:ty),*) => {$(
+        impl Checked for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+        }
+    )*};
+}
+
+impl_checked!(u8, u16, u32, u64, u128, usize);
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Range<T> {
+    dst: T,
+    src: T,
+    len: T,
+}
+
+impl<T> FromStr for Range<T>
+where
+    T: FromStr + Copy,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // 50 98 2
+        let line = Line::new(s);
+        let it: Vec<T> = s
+            .split_ascii_whitespace()
+            .map(|x| line.parse(x))
+            .collect::<Result<_, _>>()?;
+        if it.len() != 3 {
+            Err(line.error(
+                s,
+                format!("expected 3 numbers in range, found {}", it.len()),
+            ))
+        } else {
+            Ok(Range {
+                dst: it[0],
+                src: it[1],
+                len: it[2],
+            })
+        }
+    }
+}
+
+impl<T: Checked> Range<T> {
+    /// Interval of values moved by this range, if neither it nor where it
+    /// moves them runs past the largest `T`.
+    pub fn source(&self) -> Result<Interval<T>, Error> {
+        match (
+            self.src.checked_add(self.len),
+            self.dst.checked_add(self.len),
+        ) {
+            (Some(end), Some(_)) => Ok(Interval::new(self.src, end)),
+            _ => Err(Error::new(format!(
+                "range of length {} overflows",
+                self.len
+            ))),
+        }
+    }
+
+    /// Where `x` goes, for `x` in the source of this range.
+    fn map(&self, x: T) -> Result<T, Error> {
+        x.checked_sub(self.src)
+            .and_then(|offset| self.dst.checked_add(offset))
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "mapping {} by {} {} {} overflows",
+                    x, self.dst, self.src, self.len
+                ))
+            })
+    }
+}
+
+/// Interval of `len` values from `start`.
+fn seed_range<T: Checked>(start: T, len: T) -> Result<Interval<T>, Error> {
+    start
+        .checked_add(len)
+        .map(|end| Interval::new(start, end))
+        .ok_or_else(|| Error::new(format!("seed range {} + {} overflows", start, len)))
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+struct SimpleState<T: Ord> {
+    src: BTreeSet<T>,
+    dst: BTreeSet<T>,
+}
+
+impl<T: Checked> SimpleState<T> {
+    pub fn map_range(&mut self, range: &Range<T>) -> Result<(), Error> {
+        let source = range.source()?;
+        let mapped: Vec<_> = self.src.range(source.start..source.end).copied().collect();
+        for el in mapped {
+            self.dst.insert(range.map(el)?);
+            self.src.remove(&el);
+        }
+        Ok(())
+    }
+
+    pub fn finish(&mut self) {
+        self.src.append(&mut self.dst);
+    }
+}
+
+impl<T> FromIterator<T> for SimpleState<T>
+where
+    T: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            src: BTreeSet::from_iter(iter),
+            dst: BTreeSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+struct RangeState<T: Ord> {
+    src: IntervalSet<T>,
+    dst: IntervalSet<T>,
+}
+
+impl<T: Checked> RangeState<T> {
+    /// Seeds as pairs of start and length.
+    pub fn from_pairs(seeds: &[T]) -> Result<Self, Error> {
+        Ok(Self {
+            src: seeds
+                .chunks_exact(2)
+                .map(|x| seed_range(x[0], x[1]))
+                .collect::<Result<_, _>>()?,
+            dst: IntervalSet::new(),
+        })
+    }
+
+    pub fn map_range(&mut self, range: &Range<T>) -> Result<(), Error> {
+        let (mapped, unmapped) = self.src.split(range.source()?);
+        for iv in mapped.iter() {
+            self.dst
+                .insert(Interval::new(range.map(iv.start)?, range.map(iv.end)?));
+        }
+        self.src = unmapped;
+        Ok(())
+    }
+
+    pub fn finish(&mut self) {
+        self.src = self.src.union(&self.dst);
+        self.dst = IntervalSet::new();
+    }
+}
+
+/// Piecewise-linear function on `u64`: each interval of the map is moved to
+/// start at its value, everything else maps to itself.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Mapping {
+    map: IntervalMap<u64, u64>,
+}
+
+/// Where `x` goes under the piece of a mapping it falls in.
+fn shift(x: u64, rule: Option<(Interval<u64>, &u64)>) -> u64 {
+    match rule {
+        Some((src, &dst)) => dst + (x - src.start),
+        None => x,
+    }
+}
+
+impl Mapping {
+    /// Mapping of one block. Where ranges overlap the first one wins, as it
+    /// does when mapping range by range.
+    pub fn from_ranges(ranges: &[Range<u64>]) -> Result<Self, Error> {
+        let mut map = IntervalMap::new();
+        for range in ranges.iter().rev() {
+            map.insert(range.source()?, range.dst);
+        }
+        Ok(Self { map })
+    }
+
+    pub fn apply(&self, x: u64) -> u64 {
+        shift(x, self.map.get(&x))
+    }
+
+    pub fn apply_set(&self, set: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.map
+            .split(set)
+            .into_iter()
+            .map(|(iv, rule)| {
+                Interval::new(
+                    shift(iv.start, rule),
+                    shift(iv.start, rule) + (iv.end - iv.start),
+                )
+            })
+            .collect()
+    }
+
+    /// Pieces of the whole domain on which the mapping is a single shift,
+    /// with where each piece starts after mapping.
+    fn pieces(&self) -> impl Iterator<Item = (Interval<u64>, u64)> + '_ {
+        let domain = IntervalSet::from_iter([Interval::new(0, u64::MAX)]);
+        self.map
+            .split(&domain)
+            .into_iter()
+            .map(|(iv, rule)| (iv, shift(iv.start, rule)))
+    }
+
+    /// Mapping that applies this one and then `next`.
+    pub fn then(&self, next: &Mapping) -> Mapping {
+        let mut map = IntervalMap::new();
+        for (iv, start) in self.pieces() {
+            let image = IntervalSet::from_iter([Interval::new(start, start + (iv.end - iv.start))]);
+            for (sub, next_rule) in next.map.split(&image) {
+                let src = iv.start + (sub.start - start);
+                let dst = shift(sub.start, next_rule);
+                if src != dst {
+                    map.insert(Interval::new(src, src + (sub.end - sub.start)), dst);
+                }
+            }
+        }
+        Mapping { map }
+    }
+
+    /// Everything that maps into `set`.
+    pub fn preimage(&self, set: &IntervalSet<u64>) -> IntervalSet<u64> {
+        let mut result = IntervalSet::new();
+        for (iv, start) in self.pieces() {
+            let image = Interval::new(start, start + (iv.end - iv.start));
+            result.extend(set.overlapping(image).map(|hit| {
+                Interval::new(iv.start + (hit.start - start), iv.start + (hit.end - start))
+            }));
+        }
+        result
+    }
+}
+
+impl Display for Mapping {
+    /// Table of the moved intervals, one per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (iv, &dst) in self.map.iter() {
+            writeln!(
+                f,
+                "{:>12}..{:<12} -> {:>12}..{}",
+                iv.start,
+                iv.end,
+                dst,
+                dst + (iv.end - iv.start)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// One `x-to-y map:` block, mapping category `from` to category `to`.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Block {
+    from: String,
+    to: String,
+    ranges: Vec<Range<u64>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    blocks: Vec<Block>,
+}
+
+impl Almanac {
+    /// Blocks leading from category `from` to `to` in order, following the
+    /// headers rather than the order of the blocks in the input.
+    pub fn chain(&self, from: &str, to: &str) -> Result<Vec<&Block>, Error> {
+        let mut chain = Vec::new();
+        let mut current = from;
+        while current != to {
+            let block = self
+                .blocks
+                .iter()
+                .find(|b| b.from == current)
+                .ok_or_else(|| {
+                    Error::new(format!(
+                        "no map from '{}' on the way from '{}' to '{}'",
+                        current, from, to
+                    ))
+                })?;
+            // every block already used once, so this goes round in circles
+            if chain.len() == self.blocks.len() {
+                return Err(Error::new(format!(
+                    "maps from '{}' never reach '{}'",
+                    from, to
+                )));
+            }
+            chain.push(block);
+            current = &block.to;
+        }
+        Ok(chain)
+    }
+
+    /// Blocks from `from` to `to` composed into a single mapping.
+    pub fn compose(&self, from: &str, to: &str) -> Result<Mapping, Error> {
+        self.chain(from, to)?
+            .into_iter()
+            .try_fold(Mapping::default(), |acc, block| {
+                Ok(acc.then(&Mapping::from_ranges(&block.ranges)?))
+            })
+    }
+
+    /// Seeds read as pairs of start and length.
+    pub fn seed_ranges(&self) -> Result<IntervalSet<u64>, Error> {
+        self.seeds
+            .chunks_exact(2)
+            .map(|x| seed_range(x[0], x[1]))
+            .collect()
+    }
+}
+
+fn parse_seeds(line: Line) -> Result<Vec<u64>, Error> {
+    // seeds: 79 14 55 13
+    let seeds = line
+        .text
+        .strip_prefix("seeds:")
+        .ok_or_else(|| line.error(line.text, "expected 'seeds:'"))?;
+    seeds
+        .split_ascii_whitespace()
+        .map(|s| line.parse(s))
+        .collect()
+}
+
+fn parse_header(line: Line) -> Result<(String, String), Error> {
+    // seed-to-soil map:
+    let name = line.text.trim();
+    name.strip_suffix(" map:")
+        .and_then(|name| name.split_once("-to-"))
+        .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+        .map(|(from, to)| (from.to_string(), to.to_string()))
+        .ok_or_else(|| line.error(name, format!("expected 'x-to-y map:', found '{}'", name)))
+}
+
+/// Source interval of `range`, if it is non-empty, fits in `u64` and does
+/// not overlap the `sources` of earlier ranges in its block.
+fn check_range(
+    header: &str,
+    sources: &IntervalMap<u64, usize>,
+    line: Line,
+    range: &Range<u64>,
+) -> Result<Interval<u64>, Error> {
+    if range.len == 0 {
+        return Err(line.error(line.text, format!("{} range has zero length", header)));
+    }
+    let src = range
+        .source()
+        .map_err(|e| line.error(line.text, format!("{} {}", header, e.message())))?;
+    let src_set = IntervalSet::from_iter([src]);
+    if let Some((_, Some((other, other_line)))) = sources
+        .split(&src_set)
+        .into_iter()
+        .find(|(_, rule)| rule.is_some())
+    {
+        return Err(line.error(
+            line.text,
+            format!(
+                "{} source {} overlaps {} from line {}",
+                header, src, other, other_line
+            ),
+        ));
+    }
+    Ok(src)
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Almanac;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = error::lines(input);
+
+        let seeds = parse_seeds(lines.next().ok_or_else(|| Error::new("empty almanac"))?)?;
+        let mut blocks: Vec<Block> = Vec::new();
+        // line of the header of the block from each category
+        let mut headers: HashMap<String, usize> = HashMap::new();
+        let mut header = "";
+        // source intervals of the current block and their lines
+        let mut sources = IntervalMap::new();
+        while let Some(line) = lines.next() {
+            if line.text.trim().is_empty() {
+                // next block
+                let Some(line) = lines.next() else { break };
+                let (from, to) = parse_header(line)?;
+                if let Some(first) = headers.insert(from.clone(), line.number) {
+                    return Err(line.error(
+                        line.text,
+                        format!("second map from '{}', first on line {}", from, first),
+                    ));
+                }
+                header = line.text.trim();
+                sources = IntervalMap::new();
+                blocks.push(Block {
+                    from,
+                    to,
+                    ranges: Vec::new(),
+                });
+                continue;
+            }
+            let block = blocks
+                .last_mut()
+                .ok_or_else(|| line.error(line.text, "expected blank line before map"))?;
+            let range = Range::from_str(line.text).map_err(|e| line.locate(line.text, e))?;
+            sources.insert(check_range(header, &sources, line, &range)?, line.number);
+            block.ranges.push(range);
+        }
+        Ok(Almanac { seeds, blocks })
+    }
+
+    fn part1(almanac: &Self::Input) -> Result<u64, Error> {
+        let mut simple_state = SimpleState::from_iter(almanac.seeds.iter().copied());
+        for block in almanac.chain("seed", "location")? {
+            for range in &block.ranges {
+                simple_state.map_range(range)?;
+            }
+            simple_state.finish();
+        }
+        simple_state
+            .src
+            .first()
+            .copied()
+            .ok_or_else(|| Error::new("no seeds"))
+    }
+
+    fn part2(almanac: &Self::Input) -> Result<u64, Error> {
+        if almanac.seeds.len() % 2 != 0 {
+            return Err(Error::new("seeds must come in start and length pairs"));
+        }
+        let mut range_state = RangeState::from_pairs(&almanac.seeds)?;
+        for block in almanac.chain("seed", "location")? {
+            for range in &block.ranges {
+                range_state.map_range(range)?;
+            }
+            range_state.finish();
+        }
+        range_state
+            .src
+            .first()
+            .ok_or_else(|| Error::new("no seeds"))
+    }
+}
+
+const COMMANDS: &str = "commands: table [FROM TO] | seed SEED... | range START LEN \
+                        | location LOCATION... | location-range START LEN \
+                        | map FROM TO VALUE... | check";
+
+fn write_intervals(out: &mut String, set: &IntervalSet<u64>) {
+    for iv in set.iter() {
+        writeln!(out, "{}", iv).unwrap();
+    }
+}
+
+fn number(arg: &str) -> Result<u64, Error> {
+    arg.parse()
+        .map_err(|_| Error::usage(format!("invalid number '{}'", arg)))
+}
+
+fn numbers(args: &[String]) -> Result<Vec<u64>, Error> {
+    args.iter().map(|x| number(x)).collect()
+}
+
+/// Interval of `len` values from `start`, or a single value.
+fn interval(start: u64, len: u64) -> Result<IntervalSet<u64>, Error> {
+    let end = start
+        .checked_add(len)
+        .ok_or_else(|| Error::usage(format!("{} + {} overflows", start, len)))?;
+    Ok(IntervalSet::from_iter([Interval::new(start, end)]))
+}
+
+/// Queries against the almanac composed into a single mapping.
+pub fn command(input: Load, command: &str, args: &[String]) -> Result<String, Error> {
+    // every arm checks its arguments before reading the input
+    let almanac = || Day05::parse(&input()?);
+    let mut out = String::new();
+    match (command, args) {
+        ("table", []) => write!(out, "{}", almanac()?.compose("seed", "location")?).unwrap(),
+        ("table", [from, to]) => write!(out, "{}", almanac()?.compose(from, to)?).unwrap(),
+        ("map", [from, to, values @ ..]) if !values.is_empty() => {
+            let values = numbers(values)?;
+            let almanac = almanac()?;
+            let chain = almanac.chain(from, to)?;
+            let path: Vec<_> = [from.as_str()]
+                .into_iter()
+                .chain(chain.iter().map(|b| b.to.as_str()))
+                .collect();
+            writeln!(out, "{}", path.join(" -> ")).unwrap();
+            let composed = almanac.compose(from, to)?;
+            for &value in &values {
+                writeln!(
+                    out,
+                    "{} {} -> {} {}",
+                    from,
+                    value,
+                    to,
+                    composed.apply(value)
+                )
+                .unwrap();
+            }
+        }
+        ("seed", seeds) if !seeds.is_empty() => {
+            let seeds = numbers(seeds)?;
+            let composed = almanac()?.compose("seed", "location")?;
+            for &seed in &seeds {
+                writeln!(out, "seed {} -> location {}", seed, composed.apply(seed)).unwrap();
+            }
+        }
+        ("range", [start, len]) => {
+            let range = interval(number(start)?, number(len)?)?;
+            let composed = almanac()?.compose("seed", "location")?;
+            write_intervals(&mut out, &composed.apply_set(&range));
+        }
+        ("location", locations) if !locations.is_empty() => {
+            let locations = numbers(locations)?
+                .into_iter()
+                .map(|x| Ok((x, interval(x, 1)?)))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let composed = almanac()?.compose("seed", "location")?;
+            for (location, range) in &locations {
+                writeln!(out, "location {} <- seeds", location).unwrap();
+                write_intervals(&mut out, &composed.preimage(range));
+            }
+        }
+        ("location-range", [start, len]) => {
+            let range = interval(number(start)?, number(len)?)?;
+            let composed = almanac()?.compose("seed", "location")?;
+            write_intervals(&mut out, &composed.preimage(&range));
+        }
+        ("check", []) => {
+            let almanac = almanac()?;
+            let composed = almanac.compose("seed", "location")?;
+            let simple = Day05::part1(&almanac)?;
+            let single = almanac.seeds.iter().map(|&x| composed.apply(x)).min();
+            let ranges = Day05::part2(&almanac)?;
+            let seed_ranges = almanac.seed_ranges()?;
+            let composed_ranges = composed.apply_set(&seed_ranges).first();
+            // map the minimum back: it must come from a seed range, and
+            // nothing below it may
+            let back = composed
+                .preimage(&interval(ranges, 1)?)
+                .intersection(&seed_ranges);
+            let below = composed
+                .preimage(&interval(0, ranges)?)
+                .intersection(&seed_ranges);
+            let checks = [
+                (
+                    format!("part 1: SimpleState {}, composed {:?}", simple, single),
+                    single == Some(simple),
+                ),
+                (
+                    format!(
+                        "part 2: RangeState {}, composed {:?}",
+                        ranges, composed_ranges
+                    ),
+                    composed_ranges == Some(ranges),
+                ),
+                (
+                    format!(
+                        "part 2: location {} comes from seeds {}",
+                        ranges,
+                        back.iter()
+                            .map(|iv| iv.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    !back.is_empty(),
+                ),
+                (
+                    format!("part 2: no seed reaches a location below {}", ranges),
+                    below.is_empty(),
+                ),
+            ];
+            for (check, ok) in &checks {
+                writeln!(out, "{}: {}", check, if *ok { "ok" } else { "MISMATCH" }).unwrap();
+            }
+            if checks.iter().any(|(_, ok)| !ok) {
+                return Err(Error::new(format!("{}mapping strategies disagree", out)));
+            }
+        }
+        _ => return Err(Error::usage(COMMANDS)),
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Origin;
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day05::solve_part1(EXAMPLE).unwrap(), 35);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day05::solve_part2(EXAMPLE).unwrap(), 46);
+    }
+
+    #[test]
+    fn composed_mapping_matches_example() {
+        let almanac = Day05::parse(EXAMPLE).unwrap();
+        let composed = almanac.compose("seed", "location").unwrap();
+        let locations: Vec<_> = almanac.seeds.iter().map(|&x| composed.apply(x)).collect();
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+        assert_eq!(
+            composed.apply_set(&almanac.seed_ranges().unwrap()).first(),
+            Some(46)
+        );
+    }
+
+    #[test]
+    fn invalid_ranges_are_reported() {
+        let overlap = EXAMPLE.replace("52 50 48", "52 50 49");
+        let err = Day05::parse(&overlap).unwrap_err();
+        assert_eq!(err.line(), Some(5));
+        assert_eq!(
+            err.message(),
+            "seed-to-soil map: source 50..99 overlaps 98..100 from line 4"
+        );
+
+        let empty = EXAMPLE.replace("37 52 2", "37 52 0");
+        let err = Day05::parse(&empty).unwrap_err();
+        assert_eq!(err.line(), Some(9));
+        assert_eq!(
+            err.message(),
+            "soil-to-fertilizer map: range has zero length"
+        );
+
+        let overflow = EXAMPLE.replace("0 69 1", "0 18446744073709551615 1");
+        assert_eq!(Day05::parse(&overflow).unwrap_err().line(), Some(28));
+    }
+
+    #[test]
+    fn ranges_reaching_the_top_of_the_type() {
+        // 249..255 moved to 0..6 and 0..6 moved to 249..255, up to the largest
+        // end a half-open interval of u8 can have
+        let down = Range::<u8>::from_str("0 249 6").unwrap();
+        let up = Range::<u8>::from_str("249 0 6").unwrap();
+        let mut simple = SimpleState::from_iter([0u8, 5, 249, 254]);
+        simple.map_range(&down).unwrap();
+        simple.map_range(&up).unwrap();
+        simple.finish();
+        assert_eq!(simple.src, BTreeSet::from([0, 5, 249, 254]));
+
+        let mut ranges = RangeState::from_pairs(&[0u8, 10, 245, 10]).unwrap();
+        ranges.map_range(&down).unwrap();
+        ranges.map_range(&up).unwrap();
+        ranges.finish();
+        let expected: IntervalSet<u8> = [Interval::new(0, 10), Interval::new(245, 255)]
+            .into_iter()
+            .collect();
+        assert_eq!(ranges.src, expected);
+
+        let top = Range::<u64>::from_str("0 18446744073709551605 10").unwrap();
+        let mut simple = SimpleState::from_iter([u64::MAX - 1]);
+        simple.map_range(&top).unwrap();
+        assert_eq!(simple.dst, BTreeSet::from([9]));
+    }
+
+    #[test]
+    fn overflowing_mappings_are_errors() {
+        let past = Range::<u8>::from_str("0 250 7").unwrap();
+        assert_eq!(
+            SimpleState::from_iter([250u8])
+                .map_range(&past)
+                .unwrap_err()
+                .message(),
+            "range of length 7 overflows"
+        );
+        let moved_past = Range::<u8>::from_str("250 0 7").unwrap();
+        assert!(RangeState::from_pairs(&[0u8, 7])
+            .unwrap()
+            .map_range(&moved_past)
+            .is_err());
+        assert_eq!(
+            RangeState::from_pairs(&[250u8, 6]).unwrap_err().message(),
+            "seed range 250 + 6 overflows"
+        );
+
+        let seeds = EXAMPLE.replacen("79 14", "79 18446744073709551615", 1);
+        assert_eq!(
+            Day05::solve_part2(&seeds).unwrap_err().message(),
+            "seed range 79 + 18446744073709551615 overflows"
+        );
+        let top = Range::<u64>::from_str("0 18446744073709551615 1").unwrap();
+        assert!(Mapping::from_ranges(&[top]).is_err());
+    }
+
+    #[test]
+    fn blocks_are_chained_by_header() {
+        let (head, rest) = EXAMPLE.split_once("\n\n").unwrap();
+        let mut blocks: Vec<_> = rest.trim_end().split("\n\n").collect();
+        blocks.reverse();
+        let shuffled = format!("{}\n\n{}", head, blocks.join("\n\n"));
+        assert_eq!(Day05::solve_part1(&shuffled).unwrap(), 35);
+        assert_eq!(Day05::solve_part2(&shuffled).unwrap(), 46);
+
+        let almanac = Day05::parse(EXAMPLE).unwrap();
+        let chain = almanac.chain("soil", "humidity").unwrap();
+        assert_eq!(chain.first().unwrap().from, "soil");
+        assert_eq!(chain.last().unwrap().to, "humidity");
+        assert_eq!(chain.len(), 5);
+
+        let no_maps = Day05::parse("seeds: 79 14\n").unwrap();
+        assert_eq!(
+            no_maps.chain("seed", "location").unwrap_err().message(),
+            "no map from 'seed' on the way from 'seed' to 'location'"
+        );
+        let cycle = EXAMPLE.replace("humidity-to-location", "humidity-to-seed");
+        assert_eq!(
+            Day05::solve_part1(&cycle).unwrap_err().message(),
+            "maps from 'seed' never reach 'location'"
+        );
+
+        let missing = EXAMPLE.replace("water-to-light", "water-to-lamp");
+        let err = Day05::solve_part1(&missing).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "no map from 'lamp' on the way from 'seed' to 'location'"
+        );
+    }
+
+    #[test]
+    fn bad_arguments_are_usage_errors() {
+        let unread = || -> Result<String, Error> { panic!("input read for a usage error") };
+        let cases: [(&str, &[&str]); 7] = [
+            ("frob", &[]),
+            ("seed", &[]),
+            ("seed", &["x"]),
+            ("range", &["1"]),
+            ("range", &["18446744073709551615", "2"]),
+            ("location", &["18446744073709551615"]),
+            ("map", &["seed", "soil", "-1"]),
+        ];
+        for (name, args) in cases {
+            let args: Vec<_> = args.iter().map(|x| x.to_string()).collect();
+            let err = command(&unread, name, &args).unwrap_err();
+            assert_eq!(err.origin(), &Origin::Usage, "{} {:?}", name, args);
+        }
+        let input = || Ok(EXAMPLE.to_string());
+        assert_eq!(
+            command(&input, "seed", &["79".to_string()]).unwrap(),
+            "seed 79 -> location 82\n"
+        );
+    }
+
+    #[test]
+    fn preimage_inverts_example() {
+        let almanac = Day05::parse(EXAMPLE).unwrap();
+        let composed = almanac.compose("seed", "location").unwrap();
+        let location = IntervalSet::from_iter([Interval::new(46, 47)]);
+        let seeds = composed.preimage(&location);
+        assert!(seeds.contains(&82));
+        assert!(seeds
+            .iter()
+            .all(|iv| (iv.start..iv.end).all(|x| composed.apply(x) == 46)));
+        let below = IntervalSet::from_iter([Interval::new(0, 46)]);
+        assert!(composed
+            .preimage(&below)
+            .intersection(&almanac.seed_ranges().unwrap())
+            .is_empty());
+    }
+}
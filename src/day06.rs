@@ -0,0 +1,126 @@
+use crate::error::{self, Line};
+use crate::{Error, Solution};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Race {
+    time: u64,
+    distance: u64,
+}
+
+impl Race {
+    fn ways_to_win(&self) -> u64 {
+        // Holding for h gives h * (time - h) > distance, which rearranges to
+        // (time - 2h)^2 < time^2 - 4 * distance, so count the k = time - 2h
+        // of the same parity as time with k^2 < disc
+        let (time, distance) = (self.time as u128, self.distance as u128);
+        let disc = match (time * time).checked_sub(4 * distance) {
+            Some(disc) if disc > 0 => disc,
+            _ => return 0,
+        };
+        // largest m with m^2 < disc
+        let m = (disc - 1).isqrt();
+        let ways = if (m + time) % 2 == 0 { m + 1 } else { m };
+        ways as u64
+    }
+}
+
+fn parse_row(line: Option<Line>, label: &str) -> Result<Vec<u64>, Error> {
+    // Time:      7  15   30
+    let line = line.ok_or_else(|| Error::new(format!("missing '{}:' row", label)))?;
+    let values: Vec<_> = line
+        .text
+        .strip_prefix(label)
+        .and_then(|s| s.strip_prefix(':'))
+        .ok_or_else(|| line.error(line.text, format!("expected '{}:'", label)))?
+        .split_ascii_whitespace()
+        .map(|s| line.parse(s))
+        .collect::<Result<_, _>>()?;
+    if values.is_empty() {
+        return Err(line.missing("at least one race"));
+    }
+    Ok(values)
+}
+
+fn concat<'a>(values: impl Iterator<Item = &'a u64>) -> Result<u64, Error> {
+    let digits = values.map(|x| x.to_string()).collect::<String>();
+    digits
+        .parse()
+        .map_err(|_| Error::new(format!("single race {} is too long", digits)))
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<Race>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = error::lines(input);
+        let times = parse_row(lines.next(), "Time")?;
+        let distances = parse_row(lines.next(), "Distance")?;
+        if times.len() != distances.len() {
+            return Err(Error::new(format!(
+                "{} times but {} distances",
+                times.len(),
+                distances.len()
+            )));
+        }
+        Ok(times
+            .into_iter()
+            .zip(distances)
+            .map(|(time, distance)| Race { time, distance })
+            .collect())
+    }
+
+    fn part1(races: &Self::Input) -> Result<u64, Error> {
+        races
+            .iter()
+            .map(Race::ways_to_win)
+            .try_fold(1u64, u64::checked_mul)
+            .ok_or_else(|| Error::new("product of ways to win overflows"))
+    }
+
+    fn part2(races: &Self::Input) -> Result<u64, Error> {
+        // The spaces were a kerning mistake, there is only one race
+        Ok(Race {
+            time: concat(races.iter().map(|r| &r.time))?,
+            distance: concat(races.iter().map(|r| &r.distance))?,
+        }
+        .ways_to_win())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day06::solve_part1(EXAMPLE).unwrap(), 288);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day06::solve_part2(EXAMPLE).unwrap(), 71503);
+    }
+
+    #[test]
+    fn empty_rows_are_rejected() {
+        let err = Day06::parse("Time:\nDistance:\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(1), Some(6)));
+        assert_eq!(err.message(), "expected at least one race");
+    }
+
+    #[test]
+    fn product_too_large() {
+        let input = "Time: 4294967296 4294967296 4294967296\nDistance: 1 1 1\n";
+        let err = Day06::solve_part1(input).unwrap_err();
+        assert_eq!(err.message(), "product of ways to win overflows");
+    }
+}
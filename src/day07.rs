@@ -1,15 +1,17 @@
-use std::io;
 use std::str::FromStr;
 
+use crate::error::{self, Line};
+use crate::{Error, Solution};
+
 #[derive(Debug, PartialEq, Eq)]
-struct Hand {
+pub struct Hand {
     cards: [char; 5],
     bid: u64,
 }
 
 impl Hand {
     fn groups_triplets(&self) -> (usize, usize) {
-        let mut sorted = self.cards.clone();
+        let mut sorted = self.cards;
         sorted.sort_unstable();
         let groups = sorted.windows(2).filter(|x| x[0] != x[1]).count() + 1;
         let triplets = sorted
@@ -20,21 +22,30 @@ impl Hand {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseHandError;
-
 impl FromStr for Hand {
-    type Err = ParseHandError;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // 32T3K 765
-        let (cards_str, bid_str) = s.split_once(' ').ok_or(ParseHandError)?;
+        let line = Line::new(s);
+        let (cards_str, bid_str) = s.split_once(' ').ok_or_else(|| line.missing("bid"))?;
+        if let Some((i, c)) = cards_str
+            .char_indices()
+            .find(|(_, c)| !c.is_ascii() || !RankedHand::STRENGTH.contains(&(*c as u8)))
+        {
+            return Err(line.error(&cards_str[i..], format!("unknown card '{}'", c)));
+        }
         let cards: [char; 5] = cards_str
             .chars()
             .collect::<Vec<_>>()
             .try_into()
-            .map_err(|_| ParseHandError)?;
-        let bid: u64 = bid_str.parse().map_err(|_| ParseHandError)?;
+            .map_err(|_| {
+                line.error(
+                    cards_str,
+                    format!("expected 5 cards, found '{}'", cards_str),
+                )
+            })?;
+        let bid: u64 = line.parse(bid_str)?;
         Ok(Self { cards, bid })
     }
 }
@@ -131,31 +142,61 @@ impl JokerHand {
     }
 }
 
-fn main() -> io::Result<()> {
-    let hands: Vec<Hand> = io::stdin()
-        .lines()
-        .map(|s| s.as_ref().unwrap().parse().unwrap())
-        .collect();
-
-    let mut sorted: Vec<RankedHand> = hands.iter().map(|h| RankedHand::from_hand(h)).collect();
-    sorted.sort_unstable();
-    let winnings: u64 = sorted
-        .iter()
-        .rev()
-        .enumerate()
-        .map(|(i, x)| (i as u64 + 1) * x.bid)
-        .sum();
-    println!("{}", winnings);
-
-    let mut sorted: Vec<JokerHand> = hands.iter().map(|h| JokerHand::from_hand(h)).collect();
-    sorted.sort_unstable();
-    let winnings: u64 = sorted
-        .iter()
-        .rev()
-        .enumerate()
-        .map(|(i, x)| (i as u64 + 1) * x.bid)
-        .sum();
-    println!("{}", winnings);
-
-    Ok(())
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<Hand>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        error::lines(input)
+            .map(|line| line.text.parse().map_err(|e| line.locate(line.text, e)))
+            .collect()
+    }
+
+    fn part1(hands: &Self::Input) -> Result<u64, Error> {
+        let mut sorted: Vec<RankedHand> = hands.iter().map(RankedHand::from_hand).collect();
+        sorted.sort_unstable();
+        Ok(sorted
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, x)| (i as u64 + 1) * x.bid)
+            .sum())
+    }
+
+    fn part2(hands: &Self::Input) -> Result<u64, Error> {
+        let mut sorted: Vec<JokerHand> = hands.iter().map(JokerHand::from_hand).collect();
+        sorted.sort_unstable();
+        Ok(sorted
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, x)| (i as u64 + 1) * x.bid)
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day07::solve_part1(EXAMPLE).unwrap(), 6440);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day07::solve_part2(EXAMPLE).unwrap(), 5905);
+    }
 }
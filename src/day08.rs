@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use crate::error::{self, Line};
+use crate::{Error, Solution};
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    if b > a {
+        (a, b) = (b, a);
+    }
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub type Network = HashMap<String, (String, String)>;
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = (String, Network);
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = error::lines(input);
+
+        let first = lines.next().ok_or_else(|| Error::new("no instructions"))?;
+        if let Some((i, c)) = first
+            .text
+            .char_indices()
+            .find(|&(_, c)| c != 'L' && c != 'R')
+        {
+            return Err(first.error(&first.text[i..], format!("unknown instruction '{}'", c)));
+        }
+        if first.text.is_empty() {
+            return Err(first.missing("instructions"));
+        }
+        let instructions = first.text.to_string();
+        if let Some(line) = lines.next().filter(|line| !line.text.trim().is_empty()) {
+            return Err(line.error(line.text, "expected a blank line after the instructions"));
+        }
+        let mut network: Network = HashMap::new();
+        let mut edge_tokens: Vec<(Line, &str)> = Vec::new();
+        for line in lines {
+            // AAA = (BBB, CCC)
+            let (node, edges) = line
+                .text
+                .split_once('=')
+                .ok_or_else(|| line.missing("'='"))?;
+            let (left, right) = edges
+                .trim()
+                .trim_matches('(')
+                .trim_matches(')')
+                .split_once(',')
+                .ok_or_else(|| line.error(edges, "expected '(left, right)'"))?;
+            let (node, left, right) = (node.trim(), left.trim(), right.trim());
+            if network.contains_key(node) {
+                return Err(line.error(node, format!("duplicate node '{}'", node)));
+            }
+            network.insert(node.to_string(), (left.to_string(), right.to_string()));
+            edge_tokens.extend([(line, left), (line, right)]);
+        }
+        if let Some((line, token)) = edge_tokens.iter().find(|(_, t)| !network.contains_key(*t)) {
+            return Err(line.error(token, format!("unknown node '{}'", token)));
+        }
+        Ok((instructions, network))
+    }
+
+    fn part1((instructions, network): &Self::Input) -> Result<u64, Error> {
+        if !network.contains_key("AAA") {
+            return Err(Error::new("no node 'AAA'"));
+        }
+        // Every (node, instruction) state is visited by then if ZZZ is reachable
+        let limit = (network.len() * instructions.len()) as u64;
+        let mut steps = 0u64;
+        let mut current = "AAA";
+        for instruction in instructions.chars().cycle() {
+            if current == "ZZZ" {
+                break;
+            }
+            if steps > limit {
+                return Err(Error::new("'ZZZ' is not reachable from 'AAA'"));
+            }
+            let (left, right) = &network[current];
+            current = if instruction == 'L' { left } else { right };
+            steps += 1;
+        }
+        Ok(steps)
+    }
+
+    fn part2((instructions, network): &Self::Input) -> Result<u64, Error> {
+        let limit = (network.len() * instructions.len()) as u64;
+        let mut periods: Vec<u64> = Vec::new();
+        // Assume start is included in cycles, otherwise more difficult (!)
+        for start in network.keys().filter(|k| k.ends_with('A')) {
+            let mut current = start;
+            let mut first_end: HashMap<(&str, usize), u64> = HashMap::new();
+            for (steps, (i, instruction)) in (0u64..).zip(instructions.chars().enumerate().cycle())
+            {
+                if steps > 2 * limit {
+                    return Err(Error::new(format!(
+                        "no node ending in 'Z' repeats from '{}'",
+                        start
+                    )));
+                }
+                if current.ends_with('Z') {
+                    let first = first_end.entry((current, i)).or_insert(steps);
+                    if first != &steps {
+                        let period = steps - *first;
+                        periods.push(period);
+                        break;
+                    }
+                }
+                let (left, right) = &network[current];
+                current = if instruction == 'L' { left } else { right };
+            }
+        }
+        Ok(periods.iter().fold(1, |acc, &e| acc * (e / gcd(acc, e))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_REPEAT: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_GHOSTS: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day08::solve_part1(EXAMPLE).unwrap(), 2);
+        assert_eq!(Day08::solve_part1(EXAMPLE_REPEAT).unwrap(), 6);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day08::solve_part2(EXAMPLE_GHOSTS).unwrap(), 6);
+    }
+
+    #[test]
+    fn blank_line_is_required() {
+        let err = Day08::parse("LR\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(1)));
+        assert_eq!(
+            err.message(),
+            "expected a blank line after the instructions"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::error;
+use crate::{Error, Solution};
+
+/// Differences between neighbouring values, or `None` if one overflows.
+fn diffs(history: &[i64]) -> Option<Vec<i64>> {
+    history.windows(2).map(|x| x[1].checked_sub(x[0])).collect()
+}
+
+fn predict(history: &[i64]) -> Option<i64> {
+    if history.iter().all(|&x| x == 0) {
+        return Some(0);
+    }
+    history
+        .last()
+        .unwrap_or(&0)
+        .checked_add(predict(&diffs(history)?)?)
+}
+
+fn predict_back(history: &[i64]) -> Option<i64> {
+    if history.iter().all(|&x| x == 0) {
+        return Some(0);
+    }
+    history
+        .first()
+        .unwrap_or(&0)
+        .checked_sub(predict_back(&diffs(history)?)?)
+}
+
+/// Sum of `predict` over every history, naming the line where it overflows.
+fn sum(histories: &[Vec<i64>], predict: fn(&[i64]) -> Option<i64>) -> Result<i64, Error> {
+    let mut total = 0i64;
+    for (number, history) in (1..).zip(histories) {
+        total = predict(history)
+            .and_then(|x| total.checked_add(x))
+            .ok_or_else(|| Error::at(number, 1, "prediction overflows"))?;
+    }
+    Ok(total)
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<Vec<i64>>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        error::lines(input)
+            .map(|line| {
+                line.text
+                    .split_ascii_whitespace()
+                    .map(|s| line.parse(s))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn part1(histories: &Self::Input) -> Result<i64, Error> {
+        sum(histories, predict)
+    }
+
+    fn part2(histories: &Self::Input) -> Result<i64, Error> {
+        sum(histories, predict_back)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day09::solve_part1(EXAMPLE).unwrap(), 114);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day09::solve_part2(EXAMPLE).unwrap(), 2);
+    }
+
+    #[test]
+    fn predictions_too_large() {
+        let input = "1 2 3\n9223372036854775807 -9223372036854775808\n";
+        let err = Day09::solve_part1(input).unwrap_err();
+        assert_eq!((err.line(), err.column()), (Some(2), Some(1)));
+        assert_eq!(err.message(), "prediction overflows");
+        assert!(Day09::solve_part2(input).is_err());
+    }
+}
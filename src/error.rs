@@ -0,0 +1,164 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Error for puzzle input that cannot be parsed or solved, located by line
+/// and column (both 1-based) where possible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    origin: Origin,
+    location: Option<(usize, usize)>,
+    message: String,
+}
+
+/// What an error is about, which decides how it is reported.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Origin {
+    /// The puzzle input, whose name the caller knows.
+    #[default]
+    Input,
+    /// Another file, e.g. one named on the command line.
+    File(String),
+    /// The command line itself: an unknown command or a bad argument.
+    Usage,
+}
+
+impl Error {
+    /// Error that does not point at any particular part of the input.
+    pub fn new(message: impl Display) -> Self {
+        Self {
+            origin: Origin::Input,
+            location: None,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn at(line: usize, column: usize, message: impl Display) -> Self {
+        Self {
+            origin: Origin::Input,
+            location: Some((line, column)),
+            message: message.to_string(),
+        }
+    }
+
+    /// Error in how a command was used rather than in any input.
+    pub fn usage(message: impl Display) -> Self {
+        Self {
+            origin: Origin::Usage,
+            ..Self::new(message)
+        }
+    }
+
+    /// Same error, located in the file at `path` instead of the puzzle
+    /// input.
+    pub fn in_file(self, path: impl Display) -> Self {
+        Self {
+            origin: Origin::File(path.to_string()),
+            ..self
+        }
+    }
+
+    pub fn origin(&self) -> &Origin {
+        &self.origin
+    }
+
+    /// Same error with `context` (e.g. what was being parsed) before the
+    /// message.
+    pub fn context(self, context: impl Display) -> Self {
+        Self {
+            message: format!("{}: {}", context, self.message),
+            ..self
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|(line, _)| line)
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.location.map(|(_, column)| column)
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Error {
+    /// `line:column: message`, after the file for errors in another file.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Origin::File(path) = &self.origin {
+            let separator = if self.location.is_some() { ":" } else { ": " };
+            write!(f, "{}{}", path, separator)?;
+        }
+        match self.location {
+            Some((line, column)) => write!(f, "{}:{}: {}", line, column, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A line of input with its 1-based line number, for locating errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Line for parsing a standalone string, e.g. in `FromStr`, so that
+    /// errors are relative to the string and can be moved with `locate`.
+    pub fn new(text: &'a str) -> Self {
+        Self { number: 1, text }
+    }
+
+    /// Column (1-based, in characters) of `token`, which must be a slice of
+    /// this line. Anything else points at the start of the line.
+    pub fn column(&self, token: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&x| x <= self.text.len())
+            .unwrap_or(0);
+        self.text[..offset].chars().count() + 1
+    }
+
+    pub fn error(&self, token: &str, message: impl Display) -> Error {
+        Error::at(self.number, self.column(token), message)
+    }
+
+    /// Parse `token` as a number (or anything else `FromStr`).
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T, Error> {
+        token
+            .parse()
+            .map_err(|_| self.error(token, format!("invalid number '{}'", token)))
+    }
+
+    /// Move an error located relative to `part` of this line (as produced
+    /// via `Line::new(part)`) to its position in the whole input.
+    pub fn locate(&self, part: &str, err: Error) -> Error {
+        let (line, column) = err.location.unwrap_or((1, 1));
+        Error {
+            location: Some((self.number + line - 1, self.column(part) + column - 1)),
+            ..err
+        }
+    }
+
+    /// Error for a line that ended before `expected` was found.
+    pub fn missing(&self, expected: &str) -> Error {
+        Error::at(
+            self.number,
+            self.text.chars().count() + 1,
+            format!("expected {}", expected),
+        )
+    }
+}
+
+/// Numbered lines of `input`.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
@@ -0,0 +1,236 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::error;
+use crate::Error;
+
+/// Position in a grid as (row, column).
+pub type Pos = (usize, usize);
+
+/// Rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Grid from rows that must all have the same length, otherwise the
+    /// index of the first row that differs from the first one is returned.
+    pub fn from_rows<I: IntoIterator<Item = Vec<T>>>(rows: I) -> Result<Self, usize> {
+        let mut grid = Self {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        };
+        for row in rows {
+            if grid.height == 0 {
+                grid.width = row.len();
+            } else if row.len() != grid.width {
+                return Err(grid.height);
+            }
+            grid.cells.extend(row);
+            grid.height += 1;
+        }
+        Ok(grid)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): Pos) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): Pos) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Positions of all cells matching `pred`, in row-major order.
+    pub fn find_all<'a, F>(&'a self, pred: F) -> impl Iterator<Item = Pos> + 'a
+    where
+        F: Fn(&T) -> bool + 'a,
+    {
+        self.positions().filter(move |&pos| pred(&self[pos]))
+    }
+
+    /// Orthogonal neighbours of `pos` inside the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.offsets(pos, &[(-1, 0), (0, -1), (0, 1), (1, 0)])
+    }
+
+    /// Orthogonal and diagonal neighbours of `pos` inside the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.offsets(
+            pos,
+            &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        )
+    }
+
+    fn offsets(
+        &self,
+        (row, col): Pos,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Pos> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dr, dc)| {
+            let row = row.checked_add_signed(dr).filter(|&r| r < height)?;
+            let col = col.checked_add_signed(dc).filter(|&c| c < width)?;
+            Some((row, col))
+        })
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    /// Grid of the characters of each line, which must all be the same
+    /// length.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let lines: Vec<_> = error::lines(input).collect();
+        Self::from_rows(lines.iter().map(|line| line.text.chars().collect())).map_err(|row| {
+            let line = lines[row];
+            let width = lines[0].text.chars().count();
+            line.error(
+                line.text,
+                format!(
+                    "row {} has {} columns, expected {} like row 1",
+                    row + 1,
+                    line.text.chars().count(),
+                    width
+                ),
+            )
+        })
+    }
+
+    /// Grid of the characters of each line, with shorter lines padded with
+    /// `fill` to the length of the longest.
+    pub fn parse_padded(input: &str, fill: char) -> Self {
+        let width = input.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let rows = input.lines().map(|line| {
+            let mut row: Vec<_> = line.chars().collect();
+            row.resize(width, fill);
+            row
+        });
+        Self::from_rows(rows).expect("rows are padded to the same length")
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).expect("position out of bounds")
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos).expect("position out of bounds")
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbours_stay_in_bounds() {
+        let grid = Grid::parse("abc\ndef\n").unwrap();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            grid.neighbours8((1, 2)).collect::<Vec<_>>(),
+            vec![(0, 1), (0, 2), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn rows_columns_and_display() {
+        let grid = Grid::parse("abc\ndef\n").unwrap();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(
+            grid.find_all(|c| "ae".contains(*c)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1)]
+        );
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        let err = Grid::parse("abc\nde\n").unwrap_err();
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(err.message(), "row 2 has 2 columns, expected 3 like row 1");
+        let grid = Grid::parse_padded("ab\ncdé\n", '.');
+        assert_eq!(grid.to_string(), "ab.\ncdé\n");
+    }
+}
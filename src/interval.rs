@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+/// Half-open interval `start..end` of an ordered type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Ord + Copy> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        &self.start <= x && x < &self.end
+    }
+
+    /// Common part of both intervals, if any.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let iv = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!iv.is_empty()).then_some(iv)
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Set of values stored as disjoint, non-adjacent, non-empty intervals, so
+/// that adjacent and overlapping intervals are coalesced on insertion.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T: Ord> {
+    // start -> end
+    tree: BTreeMap<T, T>,
+}
+
+impl<T: Ord + Copy> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            tree: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Smallest value in the set.
+    pub fn first(&self) -> Option<T> {
+        self.tree.keys().next().copied()
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.tree
+            .range(..=x)
+            .next_back()
+            .is_some_and(|(_, end)| x < end)
+    }
+
+    /// Intervals in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.tree
+            .iter()
+            .map(|(&start, &end)| Interval { start, end })
+    }
+
+    pub fn insert(&mut self, iv: Interval<T>) {
+        if iv.is_empty() {
+            return;
+        }
+        let Interval { mut start, mut end } = iv;
+        // an interval starting before may overlap or touch
+        if let Some((&s, &e)) = self.tree.range(..start).next_back() {
+            if e >= start {
+                start = s;
+                end = end.max(e);
+            }
+        }
+        let merged: Vec<_> = self
+            .tree
+            .range(start..=end)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in merged {
+            self.tree.remove(&s);
+            end = end.max(e);
+        }
+        self.tree.insert(start, end);
+    }
+
+    pub fn remove(&mut self, iv: Interval<T>) {
+        if iv.is_empty() {
+            return;
+        }
+        let mut overlapping: Vec<_> = self
+            .tree
+            .range(..iv.start)
+            .next_back()
+            .filter(|(_, &e)| e > iv.start)
+            .map(|(&s, &e)| (s, e))
+            .into_iter()
+            .collect();
+        overlapping.extend(self.tree.range(iv.start..iv.end).map(|(&s, &e)| (s, e)));
+        for (s, e) in overlapping {
+            self.tree.remove(&s);
+            if s < iv.start {
+                self.tree.insert(s, iv.start);
+            }
+            if e > iv.end {
+                self.tree.insert(iv.end, e);
+            }
+        }
+    }
+
+    /// Parts of the set inside `iv`, clipped to it.
+    pub fn overlapping(&self, iv: Interval<T>) -> impl Iterator<Item = Interval<T>> + '_ {
+        let before = self
+            .tree
+            .range(..iv.start)
+            .next_back()
+            .map(|(&start, &end)| Interval { start, end });
+        let inside = self
+            .tree
+            .range(iv.start..)
+            .take_while(move |(&s, _)| s < iv.end)
+            .map(|(&start, &end)| Interval { start, end });
+        before
+            .into_iter()
+            .chain(inside)
+            .filter_map(move |x| x.intersect(&iv))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for iv in other.iter() {
+            result.insert(iv);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        other.iter().flat_map(|iv| self.overlapping(iv)).collect()
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for iv in other.iter() {
+            result.remove(iv);
+        }
+        result
+    }
+
+    /// Split into the parts inside and outside of `iv`.
+    pub fn split(&self, iv: Interval<T>) -> (Self, Self) {
+        let inside: Self = self.overlapping(iv).collect();
+        let mut outside = self.clone();
+        outside.remove(iv);
+        (inside, outside)
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for iv in iter {
+            set.insert(iv);
+        }
+        set
+    }
+}
+
+impl<T: Ord + Copy> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, iter: I) {
+        for iv in iter {
+            self.insert(iv);
+        }
+    }
+}
+
+/// Piece of a set split along an `IntervalMap`, with the interval of the map
+/// it falls in and its value.
+pub type Piece<'a, K, V> = (Interval<K>, Option<(Interval<K>, &'a V)>);
+
+/// Map from disjoint, non-empty intervals to values. Inserting over an
+/// existing interval replaces the overlapping part of it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalMap<K: Ord, V> {
+    // start -> (end, value)
+    tree: BTreeMap<K, (K, V)>,
+}
+
+impl<K: Ord + Copy, V: Clone> IntervalMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tree: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Intervals and their values in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = (Interval<K>, &V)> + '_ {
+        self.tree
+            .iter()
+            .map(|(&start, (end, v))| (Interval { start, end: *end }, v))
+    }
+
+    /// The interval containing `x` and its value.
+    pub fn get(&self, x: &K) -> Option<(Interval<K>, &V)> {
+        let (&start, (end, v)) = self.tree.range(..=x).next_back()?;
+        (x < end).then_some((Interval { start, end: *end }, v))
+    }
+
+    pub fn insert(&mut self, iv: Interval<K>, value: V) {
+        if iv.is_empty() {
+            return;
+        }
+        self.remove(iv);
+        self.tree.insert(iv.start, (iv.end, value));
+    }
+
+    pub fn remove(&mut self, iv: Interval<K>) {
+        if iv.is_empty() {
+            return;
+        }
+        let mut overlapping: Vec<_> = self
+            .tree
+            .range(..iv.start)
+            .next_back()
+            .filter(|(_, (e, _))| *e > iv.start)
+            .map(|(&s, _)| s)
+            .into_iter()
+            .collect();
+        overlapping.extend(self.tree.range(iv.start..iv.end).map(|(&s, _)| s));
+        for s in overlapping {
+            let (e, v) = self.tree.remove(&s).unwrap();
+            if s < iv.start {
+                self.tree.insert(s, (iv.start, v.clone()));
+            }
+            if e > iv.end {
+                self.tree.insert(iv.end, (e, v));
+            }
+        }
+    }
+
+    /// Split `set` along the intervals of this map, so that each piece lies
+    /// either inside one interval of the map (given with its value) or in a
+    /// gap between them.
+    pub fn split(&self, set: &IntervalSet<K>) -> Vec<Piece<'_, K, V>> {
+        let mut pieces = Vec::new();
+        for iv in set.iter() {
+            let mut cursor = iv.start;
+            let before = self
+                .tree
+                .range(..iv.start)
+                .next_back()
+                .filter(|(_, (e, _))| *e > iv.start);
+            let inside = self.tree.range(iv.start..).take_while(|(&s, _)| s < iv.end);
+            for (&start, (end, v)) in before.into_iter().chain(inside) {
+                if start > cursor {
+                    pieces.push((Interval::new(cursor, start), None));
+                    cursor = start;
+                }
+                let piece_end = (*end).min(iv.end);
+                pieces.push((
+                    Interval::new(cursor, piece_end),
+                    Some((Interval { start, end: *end }, v)),
+                ));
+                cursor = piece_end;
+            }
+            if cursor < iv.end {
+                pieces.push((Interval::new(cursor, iv.end), None));
+            }
+        }
+        pieces
+    }
+}
+
+impl<K: Ord + Copy, V: Clone> FromIterator<(Interval<K>, V)> for IntervalMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (Interval<K>, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (iv, v) in iter {
+            map.insert(iv, v);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    const UNIVERSE: u8 = 64;
+
+    /// Small deterministic xorshift generator, enough for property tests.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn interval(&mut self) -> Interval<u8> {
+            let a = (self.next() % UNIVERSE as u64) as u8;
+            let b = (self.next() % UNIVERSE as u64) as u8;
+            Interval::new(a.min(b), a.max(b))
+        }
+
+        fn set(&mut self) -> (IntervalSet<u8>, BTreeSet<u8>) {
+            let mut set = IntervalSet::new();
+            let mut model = BTreeSet::new();
+            for _ in 0..self.next() % 6 {
+                let iv = self.interval();
+                set.insert(iv);
+                model.extend(iv.start..iv.end);
+            }
+            (set, model)
+        }
+    }
+
+    fn points(set: &IntervalSet<u8>) -> BTreeSet<u8> {
+        set.iter().flat_map(|iv| iv.start..iv.end).collect()
+    }
+
+    fn assert_canonical(set: &IntervalSet<u8>) {
+        let ivs: Vec<_> = set.iter().collect();
+        assert!(ivs.iter().all(|iv| !iv.is_empty()), "{:?}", ivs);
+        assert!(ivs.windows(2).all(|w| w[0].end < w[1].start), "{:?}", ivs);
+    }
+
+    #[test]
+    fn set_operations_match_model() {
+        let mut rng = Rng(0x2023_1205);
+        for _ in 0..1000 {
+            let (a, model_a) = rng.set();
+            let (b, model_b) = rng.set();
+            let iv = rng.interval();
+            let model_iv: BTreeSet<u8> = (iv.start..iv.end).collect();
+
+            assert_canonical(&a);
+            assert_eq!(points(&a), model_a);
+            for x in 0..UNIVERSE {
+                assert_eq!(a.contains(&x), model_a.contains(&x));
+            }
+            assert_eq!(a.first(), model_a.first().copied());
+
+            let union = a.union(&b);
+            assert_canonical(&union);
+            assert_eq!(points(&union), &model_a | &model_b);
+
+            let intersection = a.intersection(&b);
+            assert_canonical(&intersection);
+            assert_eq!(points(&intersection), &model_a & &model_b);
+
+            let difference = a.difference(&b);
+            assert_canonical(&difference);
+            assert_eq!(points(&difference), &model_a - &model_b);
+
+            let (inside, outside) = a.split(iv);
+            assert_canonical(&inside);
+            assert_canonical(&outside);
+            assert_eq!(points(&inside), &model_a & &model_iv);
+            assert_eq!(points(&outside), &model_a - &model_iv);
+        }
+    }
+
+    #[test]
+    fn map_split_covers_set() {
+        let mut rng = Rng(0x2023_0512);
+        for _ in 0..1000 {
+            let (set, model) = rng.set();
+            let mut map = IntervalMap::new();
+            let mut model_map = [None; UNIVERSE as usize];
+            for value in 0..rng.next() % 5 {
+                let iv = rng.interval();
+                map.insert(iv, value);
+                for x in iv.start..iv.end {
+                    model_map[x as usize] = Some(value);
+                }
+            }
+            for x in 0..UNIVERSE {
+                assert_eq!(map.get(&x).map(|(_, v)| *v), model_map[x as usize]);
+            }
+
+            let pieces = map.split(&set);
+            let covered: Vec<u8> = pieces.iter().flat_map(|(iv, _)| iv.start..iv.end).collect();
+            assert_eq!(covered, model.iter().copied().collect::<Vec<_>>());
+            for (iv, rule) in pieces {
+                assert!(!iv.is_empty());
+                for x in iv.start..iv.end {
+                    assert_eq!(rule.map(|(_, v)| *v), model_map[x as usize]);
+                    if let Some((rule_iv, _)) = rule {
+                        assert!(rule_iv.contains(&x));
+                    }
+                }
+            }
+        }
+    }
+}
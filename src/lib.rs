@@ -0,0 +1,148 @@
+use std::fmt::Display;
+use std::hint::black_box;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+
+pub use error::Error;
+
+pub mod error;
+pub mod grid;
+pub mod interval;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+
+/// A solver for one day of the puzzle.
+///
+/// Parsing is kept separate from the two parts so that the parsed input can
+/// be shared between them (and timed on its own). Malformed input is reported
+/// by `parse` where possible; the parts only fail for input that is
+/// well-formed but has no answer.
+pub trait Solution {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, Error>;
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, Error>;
+
+    fn solve_part1(input: &str) -> Result<Self::Answer1, Error> {
+        Self::part1(&Self::parse(input)?)
+    }
+
+    fn solve_part2(input: &str) -> Result<Self::Answer2, Error> {
+        Self::part2(&Self::parse(input)?)
+    }
+}
+
+/// Answers to each part rendered for display, `None` where the part was not
+/// requested.
+pub type Answers = [Option<Result<String, Error>>; 2];
+
+/// Solve the requested part of `input`, or both parts if `part` is `None`.
+pub fn solve<S: Solution>(input: &str, part: Option<u8>) -> Result<Answers, Error> {
+    let input = S::parse(input)?;
+    Ok([
+        (part != Some(2)).then(|| S::part1(&input).map(|x| x.to_string())),
+        (part != Some(1)).then(|| S::part2(&input).map(|x| x.to_string())),
+    ])
+}
+
+/// Durations of repeated runs of parsing, part one and part two in that order.
+pub type Samples = [Vec<Duration>; 3];
+
+/// Time `runs` repetitions of parsing and of each part separately.
+pub fn time<S: Solution>(input: &str, runs: usize) -> Result<Samples, Error> {
+    let mut samples = [(); 3].map(|_| Vec::with_capacity(runs));
+    for _ in 0..runs {
+        let start = Instant::now();
+        let parsed = black_box(S::parse(black_box(input))?);
+        samples[0].push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part1(&parsed)?);
+        samples[1].push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part2(&parsed)?);
+        samples[2].push(start.elapsed());
+    }
+    Ok(samples)
+}
+
+/// Reads the puzzle input for a command, once it knows it needs it.
+pub type Load<'a> = &'a dyn Fn() -> Result<String, Error>;
+
+/// Extra day-specific mode run as `<day> <command> [args...]`, given a way
+/// to read the input, the command name and its arguments, returning the text
+/// to print. Unknown commands and bad arguments are reported as
+/// `Error::usage` before the input is read.
+pub type Command = fn(Load, &str, &[String]) -> Result<String, Error>;
+
+/// Day-specific mode run as `<day> stream [args...]` over standard input,
+/// for input too large to read into memory, writing its results to the
+/// output as it goes and any warnings to the second writer.
+pub type Stream =
+    fn(&mut dyn BufRead, &mut dyn Write, &mut dyn Write, &[String]) -> Result<(), Error>;
+
+/// Type-erased entry point for one day, so that days can be looked up and
+/// run by number.
+pub struct Day {
+    pub number: u8,
+    pub solve: fn(&str, Option<u8>) -> Result<Answers, Error>,
+    pub time: fn(&str, usize) -> Result<Samples, Error>,
+    pub command: Option<Command>,
+    pub stream: Option<Stream>,
+}
+
+impl Day {
+    const fn new<S: Solution>(number: u8) -> Self {
+        Self {
+            number,
+            solve: solve::<S>,
+            time: time::<S>,
+            command: None,
+            stream: None,
+        }
+    }
+
+    const fn with_command(self, command: Command) -> Self {
+        Self {
+            command: Some(command),
+            ..self
+        }
+    }
+
+    const fn with_stream(self, stream: Stream) -> Self {
+        Self {
+            stream: Some(stream),
+            ..self
+        }
+    }
+
+    pub fn get(number: u8) -> Option<&'static Day> {
+        DAYS.iter().find(|d| d.number == number)
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(1)
+        .with_command(day01::command)
+        .with_stream(day01::stream_command),
+    Day::new::<day02::Day02>(2).with_command(day02::command),
+    Day::new::<day03::Day03>(3).with_command(day03::command),
+    Day::new::<day04::Day04>(4),
+    Day::new::<day05::Day05>(5).with_command(day05::command),
+    Day::new::<day06::Day06>(6),
+    Day::new::<day07::Day07>(7),
+    Day::new::<day08::Day08>(8),
+    Day::new::<day09::Day09>(9),
+];
@@ -1,10 +1,281 @@
-use std::io;
+use std::io::{self, BufWriter, Write};
+use std::time::Duration;
+use std::{env, fs, process};
 
-fn main() -> io::Result<()> {
-    let lines = io::stdin().lines();
+use advent_of_code_2023::error::Origin;
+use advent_of_code_2023::{Day, Error, DAYS};
 
-    for line in lines {
-        println!("{}", line?);
+const USAGE: &str = "usage: advent-of-code-2023 <day> [part]
+       advent-of-code-2023 <day> <command> [args...]
+       advent-of-code-2023 <day> stream [args...] < FILE
+       advent-of-code-2023 verify [day]
+       advent-of-code-2023 bench [--runs N] [day]";
+
+const DEFAULT_RUNS: usize = 10;
+
+fn parse_arg(arg: Option<String>, name: &str) -> Option<u8> {
+    let arg = arg?;
+    match arg.parse() {
+        Ok(x) => Some(x),
+        Err(_) => {
+            eprintln!("invalid {}: {}\n{}", name, arg, USAGE);
+            process::exit(2);
+        }
+    }
+}
+
+fn get_day(number: u8) -> &'static Day {
+    Day::get(number).unwrap_or_else(|| {
+        eprintln!("day {} is not implemented", number);
+        process::exit(1);
+    })
+}
+
+/// The given day, or every day if none was given.
+fn select_days(arg: Option<String>) -> Vec<&'static Day> {
+    match parse_arg(arg, "day") {
+        Some(day) => vec![get_day(day)],
+        None => DAYS.iter().collect(),
+    }
+}
+
+fn input_path(day: u8) -> String {
+    format!("input/{:02}", day)
+}
+
+fn read_input(day: u8) -> Result<String, String> {
+    let path = input_path(day);
+    fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Error prefixed with the input file, e.g. `input/02:17:9: unknown colour`,
+/// unless it is about something else.
+fn describe(day: u8, err: &Error) -> String {
+    describe_input(&input_path(day), err)
+}
+
+fn describe_input(input: &str, err: &Error) -> String {
+    match (err.origin(), err.line()) {
+        (Origin::Input, Some(_)) => format!("{}:{}", input, err),
+        (Origin::Input, None) => format!("{}: {}", input, err),
+        _ => err.to_string(),
+    }
+}
+
+/// Report a failed command, exiting with 2 for usage errors like
+/// `parse_arg` does.
+fn fail(input: &str, err: &Error) -> ! {
+    eprintln!("{}", describe_input(input, err));
+    process::exit(if *err.origin() == Origin::Usage { 2 } else { 1 });
+}
+
+/// Known-correct answers for `input/NN`, one line per part.
+fn read_answers(day: u8) -> [Option<String>; 2] {
+    let answers = fs::read_to_string(format!("answers/{:02}", day)).unwrap_or_default();
+    let mut lines = answers
+        .lines()
+        .map(|l| Some(l.trim().to_string()).filter(|l| !l.is_empty()));
+    [lines.next().flatten(), lines.next().flatten()]
+}
+
+fn run(day: &Day, part: Option<u8>) {
+    let input = read_input(day.number).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    let answers = (day.solve)(&input, part).unwrap_or_else(|e| {
+        eprintln!("{}", describe(day.number, &e));
+        process::exit(1);
+    });
+    let mut ok = true;
+    for (i, answer) in answers.iter().enumerate() {
+        match answer {
+            Some(Ok(answer)) => println!("Day {:02} part {}: {}", day.number, i + 1, answer),
+            Some(Err(e)) => {
+                ok = false;
+                eprintln!(
+                    "Day {:02} part {}: {}",
+                    day.number,
+                    i + 1,
+                    describe(day.number, e)
+                );
+            }
+            None => {}
+        }
+    }
+    if !ok {
+        process::exit(1);
+    }
+}
+
+fn run_command(day: &Day, command: &str, args: &[String]) {
+    let Some(run) = day.command else {
+        eprintln!("day {} has no commands\n{}", day.number, USAGE);
+        process::exit(2);
+    };
+    let path = input_path(day.number);
+    let load = || fs::read_to_string(&path).map_err(|e| Error::new(e).in_file(&path));
+    match run(&load, command, args) {
+        Ok(output) => print!("{}", output),
+        Err(e) => fail(&path, &e),
+    }
+}
+
+/// Run the day's streaming mode from standard input to standard output.
+fn run_stream(day: &Day, args: &[String]) {
+    let Some(stream) = day.stream else {
+        eprintln!("day {} has no stream mode\n{}", day.number, USAGE);
+        process::exit(2);
+    };
+    let mut out = BufWriter::new(io::stdout().lock());
+    let result = stream(&mut io::stdin().lock(), &mut out, &mut io::stderr(), args);
+    let flushed = out.flush();
+    if let Err(e) = result {
+        fail("<stdin>", &e);
+    }
+    if let Err(e) = flushed {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+/// Check every day against `answers/`, returning whether all recorded
+/// answers matched.
+fn verify(days: &[&Day]) -> bool {
+    let mut ok = true;
+    for day in days {
+        let input = match read_input(day.number) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("Day {:02}: missing input ({})", day.number, e);
+                continue;
+            }
+        };
+        let answers = match (day.solve)(&input, None) {
+            Ok(answers) => answers,
+            Err(e) => {
+                ok = false;
+                println!("Day {:02}: FAIL ({})", day.number, describe(day.number, &e));
+                continue;
+            }
+        };
+        for (i, (answer, expected)) in answers
+            .into_iter()
+            .zip(read_answers(day.number))
+            .enumerate()
+        {
+            let Some(answer) = answer else { continue };
+            let status = match (answer, expected) {
+                (Ok(answer), None) => format!("missing (got {})", answer),
+                (Ok(answer), Some(expected)) if expected == answer => "pass".to_string(),
+                (Ok(answer), Some(expected)) => {
+                    ok = false;
+                    format!("FAIL (expected {}, got {})", expected, answer)
+                }
+                (Err(e), _) => {
+                    ok = false;
+                    format!("FAIL ({})", describe(day.number, &e))
+                }
+            };
+            println!("Day {:02} part {}: {}", day.number, i + 1, status);
+        }
+    }
+    ok
+}
+
+/// Minimum, median and mean of a non-empty set of samples.
+fn stats(samples: &mut [Duration]) -> [Duration; 3] {
+    samples.sort_unstable();
+    let n = samples.len();
+    let median = if n.is_multiple_of(2) {
+        (samples[n / 2 - 1] + samples[n / 2]) / 2
+    } else {
+        samples[n / 2]
+    };
+    let mean = samples.iter().sum::<Duration>() / n as u32;
+    [samples[0], median, mean]
+}
+
+fn bench(days: &[&Day], runs: usize) {
+    println!(
+        "{:<4} {:<6} {:>12} {:>12} {:>12}",
+        "Day", "Step", "Min (µs)", "Median (µs)", "Mean (µs)"
+    );
+    for day in days {
+        let input = match read_input(day.number) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        let samples = match (day.time)(&input, runs) {
+            Ok(samples) => samples,
+            Err(e) => {
+                eprintln!("{}", describe(day.number, &e));
+                continue;
+            }
+        };
+        for (step, mut samples) in ["parse", "part 1", "part 2"].iter().zip(samples) {
+            let [min, median, mean] = stats(&mut samples).map(|d| d.as_secs_f64() * 1e6);
+            println!(
+                "{:<4} {:<6} {:>12.1} {:>12.1} {:>12.1}",
+                format!("{:02}", day.number),
+                step,
+                min,
+                median,
+                mean
+            );
+        }
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("verify") {
+        args.next();
+        let days = select_days(args.next());
+        if !verify(&days) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("bench") {
+        args.next();
+        let mut runs = DEFAULT_RUNS;
+        if args.peek().map(String::as_str) == Some("--runs") {
+            args.next();
+            runs = match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => n,
+                _ => {
+                    eprintln!("--runs needs a positive number\n{}", USAGE);
+                    process::exit(2);
+                }
+            };
+        }
+        let days = select_days(args.next());
+        bench(&days, runs);
+        return;
+    }
+
+    let Some(day) = parse_arg(args.next(), "day") else {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    };
+    let day = get_day(day);
+    match args.next() {
+        Some(command) if command == "stream" => run_stream(day, &args.collect::<Vec<_>>()),
+        Some(command) if command.parse::<u8>().is_err() => {
+            run_command(day, &command, &args.collect::<Vec<_>>())
+        }
+        part => {
+            let part = parse_arg(part, "part");
+            if !matches!(part, None | Some(1) | Some(2)) {
+                eprintln!("part must be 1 or 2\n{}", USAGE);
+                process::exit(2);
+            }
+            run(day, part);
+        }
     }
-    Ok(())
 }